@@ -6,10 +6,9 @@ impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<DamageEvent>().add_systems(
             FixedUpdate,
-            (
-                collision_to_damage.after(crate::physics::advance_physics),
-                apply_damage.after(collision_to_damage),
-            ),
+            (collision_to_damage, apply_damage)
+                .chain()
+                .in_set(PhysicsSet::PostPhysics),
         );
     }
 }
@@ -7,6 +7,7 @@ pub fn collision_to_damage(
 
     cfg: Res<PhysicsConfig>,
 
+    q_stats: Query<&MovementStats>,
     q_player: Query<(), With<Player>>,
     q_enemy: Query<(), With<Enemy>>,
 ) {
@@ -17,6 +18,7 @@ pub fn collision_to_damage(
             impulse,
             v_a_n,
             v_b_n,
+            ..
         } = *ev
         {
             println!("Collision impulse: {impulse}. v_a_n: {v_a_n}. v_b_n: {v_b_n}.");
@@ -28,9 +30,12 @@ pub fn collision_to_damage(
             let b_is_enemy = q_enemy.contains(b);
 
             if (a_is_player && b_is_enemy) || (a_is_enemy && b_is_player) {
-                let victim = if a_is_enemy { a } else { b };
+                let (attacker, victim) = if a_is_enemy { (b, a) } else { (a, b) };
                 let v_n = if a_is_player { v_a_n } else { v_b_n };
-                let v_norm = v_n / cfg.max_speed;
+                let max_speed = q_stats
+                    .get(attacker)
+                    .map_or(cfg.max_speed, |stats| stats.max_speed);
+                let v_norm = v_n / max_speed;
                 let damage = (v_norm * BASE_COLLISION_DAMAGE).max(0.0);
 
                 println!("Collision damage: {damage}.");
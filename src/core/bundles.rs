@@ -39,5 +39,21 @@ impl<M: Material2d + Clone> Shape2dBundle<M> {
             collider: Collider(ColliderShape::Circle { radius }),
         }
     }
+
+    /// The mesh is expected to be built from the same hull (e.g. `meshes.add(hull)`),
+    /// so unlike the other shapes the transform isn't scaled.
+    pub fn polygon(
+        mesh: Handle<Mesh>,
+        material: Handle<M>,
+        hull: PolygonHull,
+        pos: Vec2,
+    ) -> Self {
+        Self {
+            mesh:     Mesh2d(mesh),
+            material: MeshMaterial2d(material),
+            transform: Transform::from_translation(pos.extend(0.0)),
+            collider: Collider(ColliderShape::Polygon(hull)),
+        }
+    }
 }
 
@@ -1,6 +1,7 @@
 use crate::prelude::*;
 
 #[derive(Component)]
+#[require(RigidBody::Static)]
 pub struct Wall;
 
 #[derive(Component)]
@@ -11,8 +11,28 @@ impl Plugin for InspectorPlugin {
             .register_type::<PhysicalTranslation>()
             .register_type::<PreviousPhysicalTranslation>()
             .register_type::<Acceleration>()
+            .register_type::<RigidBody>()
+            .register_type::<MovementStats>()
+            .register_type::<ExternalForce>()
+            .register_type::<ExternalImpulse>()
+            .register_type::<AreaEffector>()
+            .register_type::<Sleeping>()
+            .register_type::<SleepTimer>()
+            .register_type::<PhysicalRotation>()
+            .register_type::<PreviousPhysicalRotation>()
+            .register_type::<AngularVelocity>()
             .register_type::<PhysicsConfig>()
+            .register_type::<PhysicsTime>()
+            .register_type::<CollisionConfig>()
+            .register_type::<JointConfig>()
             .register_type::<Collider>()
+            .register_type::<Ccd>()
+            .register_type::<Sensor>()
+            .register_type::<PhysicsMaterial>()
+            .register_type::<CollisionLayers>()
+            .register_type::<DistanceJoint>()
+            .register_type::<SpringJoint>()
+            .register_type::<PinJoint>()
             .register_type::<Health>()
             .add_plugins(EguiPlugin::default())
             .add_plugins(WorldInspectorPlugin::default());
@@ -14,6 +14,7 @@ impl Plugin for InputPlugin {
                 // If we ran this in `FixedUpdate`, it would sometimes not register player input, as that schedule may run zero times per frame.
                 handle_input.in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop),
             ),
-        );
+        )
+        .add_systems(Update, handle_physics_time_input);
     }
 }
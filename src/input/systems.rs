@@ -23,3 +23,23 @@ pub fn handle_input(
         }
     }
 }
+
+/// Debug controls for the simulation clock: P pauses or resumes, the period key runs a single
+/// fixed tick while paused, and the brackets halve the time scale or double it back up to real time.
+pub fn handle_physics_time_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut time: ResMut<PhysicsTime>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        time.toggle();
+    }
+    if keyboard_input.just_pressed(KeyCode::Period) {
+        time.step();
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketLeft) {
+        time.time_scale *= 0.5;
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketRight) {
+        time.time_scale = (time.time_scale * 2.0).min(1.0);
+    }
+}
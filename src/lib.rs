@@ -17,7 +17,11 @@ pub mod prelude {
     pub use crate::physics::plugin::*;
     pub use crate::physics::bundles::*;
     pub use crate::physics::collision::*;
+    pub use crate::physics::joints::components::*;
+    pub use crate::physics::joints::plugin::*;
+    pub use crate::physics::joints::resources::*;
     pub use crate::physics::resources::*;
+    pub use crate::physics::commands::*;
 
     pub use crate::spawn::bundles::*;
     pub use crate::spawn::plugin::*;
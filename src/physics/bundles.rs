@@ -6,6 +6,14 @@ pub struct MovableBundle {
     pub velocity: Velocity,
     pub input: AccumulatedInput,
     pub acceleration: Acceleration,
-    pub phy_translation: PhysicalTranslation, // TODO: Ensure phy_translation is initialized to the same as transform
+    pub phy_translation: PhysicalTranslation, // seeded from the `Transform` when added
     pub prev_phy_translation: PreviousPhysicalTranslation,
-}
\ No newline at end of file
+}
+
+/// Lets a [`MovableBundle`] body spin. Its moment of inertia comes from its `Mass` and `Collider`.
+#[derive(Bundle, Default)]
+pub struct RotationBundle {
+    pub phy_rotation: PhysicalRotation,
+    pub prev_phy_rotation: PreviousPhysicalRotation,
+    pub angular_velocity: AngularVelocity,
+}
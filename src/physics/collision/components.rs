@@ -1,11 +1,349 @@
+use crate::physics::*;
 use crate::prelude::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
 pub enum ColliderShape {
     Circle { radius: f32 },
     Rect { half_extents: Vec2 }, // half-size in x and y (or x and z) directions
+    Polygon(PolygonHull),
+}
+
+/// Convex polygon in the collider's local space, wound counter-clockwise.
+///
+/// Vertices live in a fixed-size array so [`ColliderShape`] stays `Copy`,
+/// which caps a polygon at [`MAX_POLYGON_VERTICES`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+pub struct PolygonHull {
+    vertices: [Vec2; MAX_POLYGON_VERTICES],
+    len: usize,
 }
 
 #[derive(Debug, Component, Clone, Copy, PartialEq, Deref, DerefMut, Reflect, InspectorOptions)]
 #[reflect(Component)]
-pub struct Collider(pub ColliderShape);
\ No newline at end of file
+pub struct Collider(pub ColliderShape);
+
+/// Enables continuous collision detection against walls for a fast moving circle.
+///
+/// The path from [`PreviousPhysicalTranslation`] to [`PhysicalTranslation`] is swept every step,
+/// and the body is stopped at the first wall it touches instead of tunneling through it.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct Ccd;
+
+/// Makes a collider a trigger: overlaps are recorded in [`Contacts`] and reported as
+/// [`CollisionEvent`]s, but neither side gets pushed or bounced.
+///
+/// Static sensors don't need the `Wall` marker to be picked up by the wall grid.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct Sensor;
+
+/// Collision layers as bitmasks: which layers a collider belongs to, and which ones it collides with.
+///
+/// Two colliders only interact when each one's memberships overlap the other's filters.
+/// Colliders without it belong to every layer and collide with everything.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct CollisionLayers {
+    pub memberships: u32,
+    pub filters: u32,
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        CollisionLayers {
+            memberships: Self::ALL,
+            filters: Self::ALL,
+        }
+    }
+}
+
+impl CollisionLayers {
+    pub const ALL: u32 = u32::MAX;
+    pub const NONE: u32 = 0;
+
+    pub fn new(memberships: u32, filters: u32) -> Self {
+        Self {
+            memberships,
+            filters,
+        }
+    }
+
+    pub fn interacts_with(&self, other: &CollisionLayers) -> bool {
+        self.memberships & other.filters != 0 && other.memberships & self.filters != 0
+    }
+}
+
+/// How the values of two touching materials are merged into the one used for the contact.
+///
+/// When both sides ask for different rules, the one declared last wins
+/// (`Average` < `Min` < `Multiply` < `Max`).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Reflect, InspectorOptions,
+)]
+pub enum CombineRule {
+    #[default]
+    Average,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl CombineRule {
+    pub fn apply(self, a: f32, b: f32) -> f32 {
+        match self {
+            CombineRule::Average => (a + b) * 0.5,
+            CombineRule::Min => a.min(b),
+            CombineRule::Multiply => a * b,
+            CombineRule::Max => a.max(b),
+        }
+    }
+}
+
+/// Surface response of a collider. Colliders without one use [`PhysicsMaterial::default`],
+/// which is perfectly elastic and frictionless.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct PhysicsMaterial {
+    /// 0 = the normal speed is absorbed, 1 = perfectly elastic bounce.
+    pub restitution: f32,
+    /// Coulomb friction coefficient, scaling the normal impulse into the maximum sliding impulse.
+    pub friction: f32,
+    pub restitution_combine: CombineRule,
+    pub friction_combine: CombineRule,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        PhysicsMaterial {
+            restitution: RESTITUTION,
+            friction: FRICTION,
+            restitution_combine: CombineRule::Average,
+            friction_combine: CombineRule::Average,
+        }
+    }
+}
+
+impl PhysicsMaterial {
+    pub fn new(restitution: f32, friction: f32) -> Self {
+        Self {
+            restitution,
+            friction,
+            ..default()
+        }
+    }
+
+    pub fn with_restitution_combine(mut self, rule: CombineRule) -> Self {
+        self.restitution_combine = rule;
+        self
+    }
+
+    pub fn with_friction_combine(mut self, rule: CombineRule) -> Self {
+        self.friction_combine = rule;
+        self
+    }
+
+    /// The material used for a contact between `self` and `other`.
+    pub fn combine(&self, other: &PhysicsMaterial) -> PhysicsMaterial {
+        let restitution_combine = self.restitution_combine.max(other.restitution_combine);
+        let friction_combine = self.friction_combine.max(other.friction_combine);
+
+        PhysicsMaterial {
+            restitution: restitution_combine.apply(self.restitution, other.restitution),
+            friction: friction_combine.apply(self.friction, other.friction),
+            restitution_combine,
+            friction_combine,
+        }
+    }
+}
+
+impl ColliderShape {
+    /// Half-size of the axis-aligned box enclosing the unrotated shape.
+    pub fn half_extents(&self) -> Vec2 {
+        self.rotated_half_extents(Rot2::IDENTITY)
+    }
+
+    /// Half-size of the axis-aligned box enclosing the shape once rotated.
+    pub fn rotated_half_extents(&self, rotation: Rot2) -> Vec2 {
+        match *self {
+            ColliderShape::Circle { radius } => Vec2::splat(radius),
+            ColliderShape::Rect { half_extents } => {
+                let (sin, cos) = (rotation.sin.abs(), rotation.cos.abs());
+                Vec2::new(
+                    cos * half_extents.x + sin * half_extents.y,
+                    sin * half_extents.x + cos * half_extents.y,
+                )
+            }
+            ColliderShape::Polygon(hull) => hull
+                .transformed(Isometry2d::from_rotation(rotation))
+                .half_extents(),
+        }
+    }
+
+    /// Moment of inertia around the collider's origin, for a body of uniform density.
+    pub fn moment_of_inertia(&self, mass: f32) -> f32 {
+        match *self {
+            ColliderShape::Circle { radius } => 0.5 * mass * radius * radius,
+            ColliderShape::Rect { half_extents } => mass * half_extents.length_squared() / 3.0,
+            ColliderShape::Polygon(hull) => {
+                // Sum over the triangles fanning out from the origin, weighted by their area
+                let verts = hull.vertices();
+                let (mut numerator, mut denominator) = (0.0, 0.0);
+                for (i, &a) in verts.iter().enumerate() {
+                    let b = verts[(i + 1) % verts.len()];
+                    let cross = a.perp_dot(b).abs();
+                    numerator += cross * (a.dot(a) + a.dot(b) + b.dot(b));
+                    denominator += cross;
+                }
+                mass * numerator / (6.0 * denominator)
+            }
+        }
+    }
+}
+
+impl PolygonHull {
+    /// Convex hull of `points`, which may come in any order and include interior points.
+    ///
+    /// Returns `None` if the points don't span an area or the hull needs more than
+    /// [`MAX_POLYGON_VERTICES`] vertices.
+    pub fn new(points: &[Vec2]) -> Option<Self> {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        sorted.dedup();
+
+        // Andrew's monotone chain, lower hull then upper hull
+        let turns_left = |o: Vec2, a: Vec2, b: Vec2| (a - o).perp_dot(b - o) > 0.0;
+        let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() * 2);
+        for &p in &sorted {
+            while hull.len() >= 2 && !turns_left(hull[hull.len() - 2], hull[hull.len() - 1], p) {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        let lower_len = hull.len() + 1;
+        for &p in sorted.iter().rev().skip(1) {
+            while hull.len() >= lower_len
+                && !turns_left(hull[hull.len() - 2], hull[hull.len() - 1], p)
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop(); // last point is the first one again
+
+        if hull.len() < 3 || hull.len() > MAX_POLYGON_VERTICES {
+            return None;
+        }
+
+        let mut vertices = [Vec2::ZERO; MAX_POLYGON_VERTICES];
+        vertices[..hull.len()].copy_from_slice(&hull);
+        Some(Self {
+            vertices,
+            len: hull.len(),
+        })
+    }
+
+    /// The four corners of an axis-aligned rectangle centered on the origin.
+    pub fn rect(half_extents: Vec2) -> Self {
+        let mut vertices = [Vec2::ZERO; MAX_POLYGON_VERTICES];
+        vertices[..4].copy_from_slice(&[
+            Vec2::new(-half_extents.x, -half_extents.y),
+            Vec2::new(half_extents.x, -half_extents.y),
+            Vec2::new(half_extents.x, half_extents.y),
+            Vec2::new(-half_extents.x, half_extents.y),
+        ]);
+        Self { vertices, len: 4 }
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices[..self.len]
+    }
+
+    /// The same polygon with every vertex rotated and then moved by `iso`.
+    pub fn transformed(&self, iso: Isometry2d) -> Self {
+        let mut moved = *self;
+        moved.vertices[..self.len]
+            .iter_mut()
+            .for_each(|v| *v = iso.transform_point(*v));
+        moved
+    }
+
+    /// Half-size of a box centered on the origin that encloses every vertex.
+    pub fn half_extents(&self) -> Vec2 {
+        self.vertices()
+            .iter()
+            .fold(Vec2::ZERO, |half, v| half.max(v.abs()))
+    }
+}
+
+impl From<PolygonHull> for Mesh {
+    fn from(hull: PolygonHull) -> Self {
+        Mesh::from(ConvexPolygon::new_unchecked(
+            hull.vertices().iter().copied(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hull_drops_interior_points_and_winds_counter_clockwise() {
+        let hull = PolygonHull::new(&[
+            Vec2::new(10.0, 10.0),
+            Vec2::ZERO,
+            Vec2::new(-10.0, -10.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(-10.0, 10.0),
+            Vec2::new(10.0, 10.0),
+        ])
+        .unwrap();
+        let verts = hull.vertices();
+        assert_eq!(verts.len(), 4);
+        assert!(!verts.contains(&Vec2::ZERO));
+        for (i, &a) in verts.iter().enumerate() {
+            let b = verts[(i + 1) % verts.len()];
+            assert!(a.perp_dot(b) > 0.0, "{verts:?}");
+        }
+    }
+
+    #[test]
+    fn degenerate_or_oversized_hulls_are_rejected() {
+        assert!(PolygonHull::new(&[Vec2::ZERO, Vec2::X, Vec2::X * 2.0]).is_none());
+        assert!(PolygonHull::new(&[Vec2::ZERO, Vec2::X]).is_none());
+
+        let circle = |n: usize| -> Vec<Vec2> {
+            (0..n)
+                .map(|i| Vec2::from_angle(i as f32 / n as f32 * std::f32::consts::TAU) * 10.0)
+                .collect()
+        };
+        assert!(PolygonHull::new(&circle(MAX_POLYGON_VERTICES)).is_some());
+        assert!(PolygonHull::new(&circle(MAX_POLYGON_VERTICES + 1)).is_none());
+    }
+
+    #[test]
+    fn materials_combine_with_the_strongest_rule() {
+        let bouncy = PhysicsMaterial::new(1.0, 0.2);
+        let dull = PhysicsMaterial::new(0.0, 0.6).with_restitution_combine(CombineRule::Min);
+        let combined = bouncy.combine(&dull);
+        assert_eq!(combined.restitution, 0.0);
+        assert!((combined.friction - 0.4).abs() < 1e-6);
+        assert_eq!(combined, dull.combine(&bouncy));
+
+        let grippy = PhysicsMaterial::new(0.5, 0.9).with_friction_combine(CombineRule::Max);
+        assert_eq!(bouncy.combine(&grippy).friction, 0.9);
+    }
+
+    #[test]
+    fn rotated_bounds_grow_to_fit() {
+        let shape = ColliderShape::Rect {
+            half_extents: Vec2::new(20.0, 10.0),
+        };
+        assert_eq!(shape.half_extents(), Vec2::new(20.0, 10.0));
+        let turned = shape.rotated_half_extents(Rot2::degrees(90.0));
+        assert!(turned.abs_diff_eq(Vec2::new(10.0, 20.0), 1e-4));
+        let diagonal = shape.rotated_half_extents(Rot2::degrees(45.0));
+        assert!(diagonal.abs_diff_eq(Vec2::splat(30.0 / 2f32.sqrt()), 1e-4));
+    }
+}
@@ -1 +1,22 @@
-pub const RESTITUTION: f32 = 1.0;
\ No newline at end of file
+pub const RESTITUTION: f32 = 1.0;
+pub const FRICTION: f32 = 0.0;
+
+/// Most vertices a polygon collider can have. Eight is plenty for level geometry
+/// and keeps `ColliderShape` small enough to copy around.
+pub const MAX_POLYGON_VERTICES: usize = 8;
+
+/// Side length of a broadphase cell. Roughly the diameter of a typical character works well.
+pub const DEFAULT_BROADPHASE_CELL_SIZE: f32 = 128.0;
+
+/// Side length of a wall grid cell. Walls tend to be large, so this is coarser than the broadphase.
+pub const DEFAULT_WALL_CELL_SIZE: f32 = 256.0;
+
+/// Gap left between a swept circle and the wall it hit, so it isn't treated as overlapping.
+pub const CCD_SKIN: f32 = 0.01;
+
+/// Vertices this close to the deepest one count as the same contact feature,
+/// so flat edge-on-edge contacts get a point in the middle of the edge.
+pub const CONTACT_FEATURE_TOLERANCE: f32 = 0.5;
+
+/// Solver passes over the contacts of each sub-step.
+pub const DEFAULT_SOLVER_ITERATIONS: u32 = 4;
@@ -1,5 +1,11 @@
+use crate::physics::*;
 use crate::prelude::*;
 
+/// Contact events for every touching pair, once per fixed timestep.
+///
+/// `normal` points from `b` to `a`, `point` is in world space, and `duration` is how long,
+/// in seconds, the pair has been touching. `Stopped` repeats the last contact seen.
+/// Every `Started` is eventually followed by a `Stopped` for the same pair.
 #[derive(Message)]
 pub enum CollisionEvent {
     Started {
@@ -8,10 +14,59 @@ pub enum CollisionEvent {
         impulse: f32, /* impulse N·s */
         v_a_n: f32,
         v_b_n: f32,
+        normal: Vec2,
+        point: Vec2,
+        penetration: f32,
+        duration: f32,
+    },
+
+    Persisting {
+        a: Entity,
+        b: Entity,
+        impulse: f32,
+        normal: Vec2,
+        point: Vec2,
+        penetration: f32,
+        duration: f32,
     },
 
     Stopped {
         a: Entity,
         b: Entity,
+        normal: Vec2,
+        point: Vec2,
+        penetration: f32,
+        duration: f32,
+        reason: StopReason,
     },
 }
+
+/// Why a pair stopped touching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The two colliders moved apart, or stopped interacting.
+    Separated,
+    /// One of them was despawned or lost its `Collider`.
+    Despawned,
+}
+
+/// Triggered on each of the two entities when they start touching, alongside
+/// [`CollisionEvent::Started`].
+///
+/// `data` is seen from the target: A is `entity`, B is `other`.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct CollisionStart {
+    pub entity: Entity,
+    pub other: Entity,
+    pub data: ContactData,
+}
+
+/// Triggered on each of the two entities when they stop touching, alongside
+/// [`CollisionEvent::Stopped`]. `data` is the last contact seen, from the target's side.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct CollisionEnd {
+    pub entity: Entity,
+    pub other: Entity,
+    pub data: ContactData,
+    pub reason: StopReason,
+}
@@ -4,10 +4,14 @@ pub mod components;
 pub mod events;
 pub mod resources;
 pub mod constants;
+pub mod narrowphase;
+pub mod spatial_query;
 
 pub use plugin::*;
 pub use systems::*;
 pub use components::*;
 pub use events::*;
 pub use resources::*;
-pub use constants::*;
\ No newline at end of file
+pub use constants::*;
+pub use narrowphase::*;
+pub use spatial_query::*;
\ No newline at end of file
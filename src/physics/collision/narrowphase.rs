@@ -0,0 +1,644 @@
+use crate::physics::*;
+use crate::prelude::*;
+
+/// Overlap between two shapes, as found by [`collide`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub normal: Vec2, // points *from* B to A
+    pub penetration: f32,
+    pub point: Vec2, // world space, midway between the two surfaces
+}
+
+impl Contact {
+    pub fn flipped(self) -> Self {
+        Contact {
+            normal: -self.normal,
+            ..self
+        }
+    }
+}
+
+/// First touch of a swept shape, as a fraction of the sweep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeOfImpact {
+    pub t: f32,       // 0 = start of the sweep, 1 = end
+    pub normal: Vec2, // points *from* the obstacle to the swept shape
+}
+
+/// Narrowphase test for any pair of shapes, A placed by `a_iso` and B by `b_iso`.
+///
+/// Axis-aligned rectangles take the cheap box paths, rotated ones go through SAT like polygons.
+pub fn collide(
+    a: &ColliderShape,
+    a_iso: Isometry2d,
+    b: &ColliderShape,
+    b_iso: Isometry2d,
+) -> Option<Contact> {
+    let (a_pos, b_pos) = (a_iso.translation, b_iso.translation);
+    let a_aligned = a_iso.rotation.is_near_identity();
+    let b_aligned = b_iso.rotation.is_near_identity();
+
+    match (*a, *b) {
+        (ColliderShape::Circle { radius: ra }, ColliderShape::Circle { radius: rb }) => {
+            circle_vs_circle(a_pos, ra, b_pos, rb)
+        }
+        (ColliderShape::Circle { radius }, ColliderShape::Rect { half_extents }) if b_aligned => {
+            circle_vs_rect(a_pos, radius, b_pos, half_extents)
+        }
+        (ColliderShape::Rect { half_extents }, ColliderShape::Circle { radius }) if a_aligned => {
+            circle_vs_rect(b_pos, radius, a_pos, half_extents).map(Contact::flipped)
+        }
+        (ColliderShape::Rect { half_extents: ha }, ColliderShape::Rect { half_extents: hb })
+            if a_aligned && b_aligned =>
+        {
+            rect_vs_rect(a_pos, ha, b_pos, hb)
+        }
+        _ => polygon_contact(a, a_iso, b, b_iso),
+    }
+}
+
+/// Normal points from the rectangle to the circle.
+fn circle_vs_rect(circle_pos: Vec2, radius: f32, rect_pos: Vec2, half: Vec2) -> Option<Contact> {
+    // closest point on the rectangle to the circle centre
+    let delta = circle_pos - rect_pos;
+    let clamped = delta.clamp(-half, half);
+    let closest = rect_pos + clamped;
+
+    let diff = circle_pos - closest;
+    let dist_sq = diff.length_squared();
+    if dist_sq > radius * radius {
+        return None; // no overlap
+    }
+
+    let dist = dist_sq.sqrt();
+    let normal = if dist != 0.0 {
+        diff / dist
+    } else if delta.x.abs() > delta.y.abs() {
+        Vec2::new(delta.x.signum(), 0.0)
+    } else {
+        Vec2::new(0.0, delta.y.signum())
+    };
+    let penetration = radius - dist;
+    Some(Contact {
+        normal,
+        penetration,
+        point: circle_pos - normal * (radius - penetration * 0.5),
+    })
+}
+
+/// Normal points from circle 2 to circle 1.
+fn circle_vs_circle(p1: Vec2, r1: f32, p2: Vec2, r2: f32) -> Option<Contact> {
+    let diff = p1 - p2;
+    let dist_sq = diff.length_squared();
+    let r_sum = r1 + r2;
+    if dist_sq >= r_sum * r_sum {
+        return None;
+    }
+    let dist = dist_sq.sqrt();
+    let normal = if dist > 0.0 {
+        diff / dist
+    } else {
+        Vec2::ONE.normalize()
+    };
+    let penetration = r_sum - dist;
+    Some(Contact {
+        normal,
+        penetration,
+        point: p1 - normal * (r1 - penetration * 0.5),
+    })
+}
+
+/// Axis-aligned boxes, separated along the axis of least overlap.
+fn rect_vs_rect(a_pos: Vec2, a_half: Vec2, b_pos: Vec2, b_half: Vec2) -> Option<Contact> {
+    let delta = a_pos - b_pos;
+    let overlap = a_half + b_half - delta.abs();
+    if overlap.x <= 0.0 || overlap.y <= 0.0 {
+        return None;
+    }
+
+    // Centre of the overlapping region
+    let lo = (a_pos - a_half).max(b_pos - b_half);
+    let hi = (a_pos + a_half).min(b_pos + b_half);
+    let point = (lo + hi) * 0.5;
+
+    Some(if overlap.x < overlap.y {
+        Contact {
+            normal: Vec2::new(delta.x.signum(), 0.0),
+            penetration: overlap.x,
+            point,
+        }
+    } else {
+        Contact {
+            normal: Vec2::new(0.0, delta.y.signum()),
+            penetration: overlap.y,
+            point,
+        }
+    })
+}
+
+/// Separating axis test for a pair where at least one shape is a polygon or a rotated rectangle.
+/// Rectangles are treated as four-sided polygons. Normal points from B to A.
+fn polygon_contact(
+    a: &ColliderShape,
+    a_iso: Isometry2d,
+    b: &ColliderShape,
+    b_iso: Isometry2d,
+) -> Option<Contact> {
+    match (*a, *b) {
+        (ColliderShape::Circle { radius }, _) => {
+            circle_vs_polygon(a_iso.translation, radius, &world_hull(b, b_iso)?)
+        }
+        (_, ColliderShape::Circle { radius }) => {
+            circle_vs_polygon(b_iso.translation, radius, &world_hull(a, a_iso)?)
+                .map(Contact::flipped)
+        }
+        _ => polygon_vs_polygon(&world_hull(a, a_iso)?, &world_hull(b, b_iso)?),
+    }
+}
+
+fn world_hull(shape: &ColliderShape, iso: Isometry2d) -> Option<PolygonHull> {
+    match *shape {
+        ColliderShape::Circle { .. } => None,
+        ColliderShape::Rect { half_extents } => {
+            Some(PolygonHull::rect(half_extents).transformed(iso))
+        }
+        ColliderShape::Polygon(hull) => Some(hull.transformed(iso)),
+    }
+}
+
+fn polygon_vs_polygon(a: &PolygonHull, b: &PolygonHull) -> Option<Contact> {
+    // The polygon owning the separating axis is the reference, the other one the incident.
+    let axes = edge_normals(a)
+        .map(|axis| (axis, true))
+        .chain(edge_normals(b).map(|axis| (axis, false)));
+
+    let mut best: Option<(Vec2, f32, bool)> = None;
+    for (axis, a_is_reference) in axes {
+        let (normal, penetration) = axis_overlap(project(a, axis), project(b, axis), axis)?;
+        if best.is_none_or(|(_, best, _)| penetration < best) {
+            best = Some((normal, penetration, a_is_reference));
+        }
+    }
+
+    let (normal, penetration, a_is_reference) = best?;
+    // The incident polygon's deepest vertices sit inside the reference one
+    let point = if a_is_reference {
+        support_point(b, normal) - normal * penetration * 0.5
+    } else {
+        support_point(a, -normal) + normal * penetration * 0.5
+    };
+    Some(Contact {
+        normal,
+        penetration,
+        point,
+    })
+}
+
+/// Normal points from the polygon to the circle.
+fn circle_vs_polygon(center: Vec2, radius: f32, poly: &PolygonHull) -> Option<Contact> {
+    // Besides the edge normals, the axis towards the closest vertex covers the corner regions
+    let closest = poly.vertices().iter().copied().min_by(|a, b| {
+        a.distance_squared(center)
+            .total_cmp(&b.distance_squared(center))
+    })?;
+    let vertex_axis = (center - closest).try_normalize();
+
+    let mut best: Option<(Vec2, f32)> = None;
+    for axis in edge_normals(poly).chain(vertex_axis) {
+        let c = center.dot(axis);
+        let (normal, penetration) =
+            axis_overlap((c - radius, c + radius), project(poly, axis), axis)?;
+        if best.is_none_or(|(_, best)| penetration < best) {
+            best = Some((normal, penetration));
+        }
+    }
+
+    let (normal, penetration) = best?;
+    Some(Contact {
+        normal,
+        penetration,
+        point: center - normal * (radius - penetration * 0.5),
+    })
+}
+
+/// Outward unit normals of a counter-clockwise polygon.
+fn edge_normals(poly: &PolygonHull) -> impl Iterator<Item = Vec2> + '_ {
+    let verts = poly.vertices();
+    (0..verts.len()).map(move |i| {
+        let edge = verts[(i + 1) % verts.len()] - verts[i];
+        Vec2::new(edge.y, -edge.x).normalize_or_zero()
+    })
+}
+
+fn project(poly: &PolygonHull, axis: Vec2) -> (f32, f32) {
+    poly.vertices()
+        .iter()
+        .map(|v| v.dot(axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| {
+            (lo.min(d), hi.max(d))
+        })
+}
+
+/// Average of the vertices furthest along `dir`. Averaging the ones within a small tolerance
+/// puts the point in the middle of an edge lying flat against the other shape.
+fn support_point(poly: &PolygonHull, dir: Vec2) -> Vec2 {
+    let (_, max) = project(poly, dir);
+    let (sum, count) = poly
+        .vertices()
+        .iter()
+        .filter(|v| v.dot(dir) >= max - CONTACT_FEATURE_TOLERANCE)
+        .fold((Vec2::ZERO, 0.0), |(sum, count), &v| (sum + v, count + 1.0));
+    sum / count
+}
+
+/// Overlap of A's and B's projections on one axis, as the normal pointing the way A has
+/// to move to get out and the penetration depth. `None` if the axis separates them.
+fn axis_overlap(a: (f32, f32), b: (f32, f32), axis: Vec2) -> Option<(Vec2, f32)> {
+    let backward = a.1 - b.0; // move A along -axis
+    let forward = b.1 - a.0; // move A along +axis
+    if backward <= 0.0 || forward <= 0.0 {
+        return None;
+    }
+
+    Some(if backward < forward {
+        (-axis, backward)
+    } else {
+        (axis, forward)
+    })
+}
+
+/// Whether `point` lies inside the shape placed by `iso`, boundary included.
+pub fn contains_point(shape: &ColliderShape, iso: Isometry2d, point: Vec2) -> bool {
+    let local = iso.inverse_transform_point(point);
+    match *shape {
+        ColliderShape::Circle { radius } => local.length_squared() <= radius * radius,
+        ColliderShape::Rect { half_extents } => local.abs().cmple(half_extents).all(),
+        ColliderShape::Polygon(hull) => {
+            let verts = hull.vertices();
+            (0..verts.len()).all(|i| {
+                let edge = verts[(i + 1) % verts.len()] - verts[i];
+                edge.perp_dot(local - verts[i]) >= 0.0
+            })
+        }
+    }
+}
+
+/// Swept circle vs any shape. A zero radius turns this into a raycast.
+/// Sweeps starting in overlap return `None`, like [`sweep_circle_vs_rect`].
+pub fn sweep_circle(
+    from: Vec2,
+    to: Vec2,
+    radius: f32,
+    shape: &ColliderShape,
+    iso: Isometry2d,
+) -> Option<TimeOfImpact> {
+    match *shape {
+        ColliderShape::Circle { radius: r } => {
+            let motion = to - from;
+            let t = ray_vs_circle(from, motion, iso.translation, r + radius)?;
+            Some(TimeOfImpact {
+                t,
+                normal: (from + motion * t - iso.translation).normalize_or_zero(),
+            })
+        }
+        ColliderShape::Rect { half_extents } => {
+            sweep_circle_vs_rect(from, to, radius, iso, half_extents)
+        }
+        ColliderShape::Polygon(hull) => {
+            sweep_circle_vs_polygon(from, to, radius, &hull.transformed(iso))
+        }
+    }
+}
+
+/// Ray against the polygon grown by the radius: its edges pushed out along their normals,
+/// joined by circles around the vertices.
+fn sweep_circle_vs_polygon(
+    from: Vec2,
+    to: Vec2,
+    radius: f32,
+    poly: &PolygonHull,
+) -> Option<TimeOfImpact> {
+    let motion = to - from;
+    let verts = poly.vertices();
+    let mut first: Option<TimeOfImpact> = None;
+    let mut keep_first = |toi: TimeOfImpact| {
+        if first.is_none_or(|best| toi.t < best.t) {
+            first = Some(toi);
+        }
+    };
+
+    for (i, normal) in edge_normals(poly).enumerate() {
+        let speed = motion.dot(normal);
+        if speed >= 0.0 {
+            continue; // moving away from, or along, this edge
+        }
+        let (a, b) = (verts[i], verts[(i + 1) % verts.len()]);
+        let t = ((a - from).dot(normal) + radius) / speed;
+        let along = (from + motion * t - a).dot(b - a);
+        if (0.0..=1.0).contains(&t) && (0.0..=(b - a).length_squared()).contains(&along) {
+            keep_first(TimeOfImpact { t, normal });
+        }
+    }
+    for &v in verts {
+        if let Some(t) = ray_vs_circle(from, motion, v, radius) {
+            let normal = (from + motion * t - v).normalize_or_zero();
+            keep_first(TimeOfImpact { t, normal });
+        }
+    }
+    first
+}
+
+/// Swept circle vs rectangle, i.e. a ray against the rectangle grown by the radius
+/// (a rounded rectangle). Sweeps starting in overlap are left to the discrete test.
+///
+/// Works in the rectangle's local frame, so rotated rectangles are handled as well.
+pub fn sweep_circle_vs_rect(
+    from: Vec2,
+    to: Vec2,
+    radius: f32,
+    rect_iso: Isometry2d,
+    half: Vec2,
+) -> Option<TimeOfImpact> {
+    let origin = rect_iso.inverse_transform_point(from);
+    let motion = rect_iso.inverse_transform_point(to) - origin;
+    let grown = half + Vec2::splat(radius);
+
+    // Slab test against the grown box
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+    for axis in 0..2 {
+        let (o, m, e) = (origin[axis], motion[axis], grown[axis]);
+        if m.abs() < f32::EPSILON {
+            if o.abs() > e {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (-e - o) / m;
+        let t2 = (e - o) / m;
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > t_enter {
+            t_enter = near;
+            normal = Vec2::ZERO;
+            normal[axis] = -m.signum();
+        }
+        t_exit = t_exit.min(far);
+    }
+    if t_enter > t_exit || !(0.0..=1.0).contains(&t_enter) {
+        return None;
+    }
+
+    // Entering through a corner of the grown box means the real hit, if any, is on the
+    // rounded corner.
+    let hit = origin + motion * t_enter;
+    if hit.x.abs() > half.x && hit.y.abs() > half.y {
+        let corner = Vec2::new(half.x.copysign(hit.x), half.y.copysign(hit.y));
+        let t = ray_vs_circle(origin, motion, corner, radius)?;
+        let normal = (origin + motion * t - corner).normalize_or_zero();
+        return Some(TimeOfImpact {
+            t,
+            normal: rect_iso.rotation * normal,
+        });
+    }
+
+    Some(TimeOfImpact {
+        t: t_enter,
+        normal: rect_iso.rotation * normal,
+    })
+}
+
+/// Fraction of `motion` at which a ray starting at `origin` enters the circle, if within [0, 1].
+fn ray_vs_circle(origin: Vec2, motion: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let rel = origin - center;
+    let a = motion.length_squared();
+    let b = 2.0 * motion.dot(rel);
+    let c = rel.length_squared() - radius * radius;
+
+    let disc = b * b - 4.0 * a * c;
+    if a < f32::EPSILON || disc < 0.0 {
+        return None;
+    }
+    let t = (-b - disc.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    const EPS: f32 = 1e-4;
+
+    fn at(x: f32, y: f32) -> Isometry2d {
+        Isometry2d::from_translation(Vec2::new(x, y))
+    }
+
+    fn circle(radius: f32) -> ColliderShape {
+        ColliderShape::Circle { radius }
+    }
+
+    fn rect(x: f32, y: f32) -> ColliderShape {
+        ColliderShape::Rect {
+            half_extents: Vec2::new(x, y),
+        }
+    }
+
+    fn triangle() -> ColliderShape {
+        let hull = PolygonHull::new(&[
+            Vec2::new(-10.0, -10.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(0.0, 10.0),
+        ])
+        .unwrap();
+        ColliderShape::Polygon(hull)
+    }
+
+    #[test]
+    fn circles_overlap_along_the_centre_line() {
+        let contact = collide(&circle(10.0), at(15.0, 0.0), &circle(10.0), at(0.0, 0.0)).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::X, EPS));
+        assert!((contact.penetration - 5.0).abs() < EPS);
+
+        assert!(collide(&circle(10.0), at(25.0, 0.0), &circle(10.0), at(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn circle_vs_rect_normal_points_at_the_circle_either_way_round() {
+        let contact =
+            collide(&circle(5.0), at(0.0, 13.0), &rect(20.0, 10.0), at(0.0, 0.0)).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPS));
+        assert!((contact.penetration - 2.0).abs() < EPS);
+
+        let flipped =
+            collide(&rect(20.0, 10.0), at(0.0, 0.0), &circle(5.0), at(0.0, 13.0)).unwrap();
+        assert!(flipped.normal.abs_diff_eq(Vec2::NEG_Y, EPS));
+        assert!((flipped.penetration - 2.0).abs() < EPS);
+    }
+
+    #[test]
+    fn rects_separate_along_the_shallowest_axis() {
+        let contact = collide(
+            &rect(10.0, 10.0),
+            at(18.0, 5.0),
+            &rect(10.0, 10.0),
+            at(0.0, 0.0),
+        )
+        .unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::X, EPS));
+        assert!((contact.penetration - 2.0).abs() < EPS);
+
+        assert!(
+            collide(
+                &rect(10.0, 10.0),
+                at(21.0, 0.0),
+                &rect(10.0, 10.0),
+                at(0.0, 0.0)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn rotated_rect_goes_through_sat() {
+        // A square turned 45° reaches √2 · 10 along the axes
+        let turned = Isometry2d::new(Vec2::new(0.0, 22.0), Rot2::radians(FRAC_PI_4));
+        let contact = collide(&rect(10.0, 10.0), turned, &rect(50.0, 10.0), at(0.0, 0.0)).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPS));
+        assert!((contact.penetration - (10.0 * 2f32.sqrt() - 12.0)).abs() < 1e-3);
+
+        let apart = Isometry2d::new(Vec2::new(0.0, 25.0), Rot2::radians(FRAC_PI_4));
+        assert!(collide(&rect(10.0, 10.0), apart, &rect(50.0, 10.0), at(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn polygon_contacts_follow_the_edge_normals() {
+        // Resting on the triangle's flat bottom edge from below
+        let contact = collide(&circle(5.0), at(0.0, -14.0), &triangle(), at(0.0, 0.0)).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::NEG_Y, EPS));
+        assert!((contact.penetration - 1.0).abs() < EPS);
+
+        // Past the tip, the closest feature is the vertex
+        assert!(collide(&circle(5.0), at(0.0, 16.0), &triangle(), at(0.0, 0.0)).is_none());
+        let contact = collide(&circle(5.0), at(0.0, 14.0), &triangle(), at(0.0, 0.0)).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPS));
+
+        // Either way round, the normal points from B to A
+        let contact = collide(&triangle(), at(0.0, 0.0), &rect(50.0, 5.0), at(0.0, -14.0)).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPS));
+        assert!((contact.penetration - 1.0).abs() < EPS);
+        let contact = collide(&rect(50.0, 5.0), at(0.0, -14.0), &triangle(), at(0.0, 0.0)).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::NEG_Y, EPS));
+    }
+
+    #[test]
+    fn sweeps_stop_at_the_first_touch() {
+        let (from, to) = (Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0));
+        for shape in [circle(10.0), rect(10.0, 10.0), triangle()] {
+            let toi = sweep_circle(from, to, 5.0, &shape, at(0.0, 0.0)).unwrap();
+            let touch = from + (to - from) * toi.t;
+            assert!(touch.x < -10.0, "{shape:?} {touch:?}");
+            assert!(toi.normal.x < 0.0, "{shape:?} {:?}", toi.normal);
+            assert!(collide(&circle(5.0), at(touch.x + 0.1, 0.0), &shape, at(0.0, 0.0)).is_some());
+        }
+
+        let toi = sweep_circle(from, to, 5.0, &rect(10.0, 10.0), at(0.0, 0.0)).unwrap();
+        assert!((toi.t - 85.0 / 200.0).abs() < EPS);
+        assert!(toi.normal.abs_diff_eq(Vec2::NEG_X, EPS));
+
+        // Through the rounded corner of the grown box
+        let (from, to) = (Vec2::new(-100.0, 13.0), Vec2::new(100.0, 13.0));
+        let toi = sweep_circle_vs_rect(from, to, 5.0, at(0.0, 0.0), Vec2::splat(10.0)).unwrap();
+        let touch = from + (to - from) * toi.t;
+        assert!((touch.distance(Vec2::new(-10.0, 10.0)) - 5.0).abs() < 1e-3);
+        assert!(toi.normal.x < 0.0 && toi.normal.y > 0.0);
+    }
+
+    #[test]
+    fn sweeps_that_miss_or_start_inside_find_nothing() {
+        let half = Vec2::splat(10.0);
+        let miss = sweep_circle_vs_rect(
+            Vec2::new(-100.0, 20.0),
+            Vec2::new(100.0, 20.0),
+            5.0,
+            at(0.0, 0.0),
+            half,
+        );
+        assert!(miss.is_none());
+        let short = sweep_circle_vs_rect(
+            Vec2::new(-100.0, 0.0),
+            Vec2::new(-50.0, 0.0),
+            5.0,
+            at(0.0, 0.0),
+            half,
+        );
+        assert!(short.is_none());
+        let inside =
+            sweep_circle_vs_rect(Vec2::ZERO, Vec2::new(100.0, 0.0), 5.0, at(0.0, 0.0), half);
+        assert!(inside.is_none());
+    }
+
+    #[test]
+    fn rotated_rect_sweep_reports_a_world_normal() {
+        // Long side along the diagonal, approached square on
+        let turned = Isometry2d::new(Vec2::ZERO, Rot2::radians(FRAC_PI_4));
+        let toi = sweep_circle_vs_rect(
+            Vec2::new(100.0, -100.0),
+            Vec2::ZERO,
+            0.0,
+            turned,
+            Vec2::new(50.0, 10.0),
+        )
+        .unwrap();
+        let expected = Vec2::new(1.0, -1.0).normalize();
+        assert!(toi.normal.abs_diff_eq(expected, EPS), "{:?}", toi.normal);
+    }
+
+    #[test]
+    fn contains_point_includes_the_boundary() {
+        assert!(contains_point(
+            &circle(10.0),
+            at(5.0, 0.0),
+            Vec2::new(15.0, 0.0)
+        ));
+        assert!(!contains_point(
+            &circle(10.0),
+            at(5.0, 0.0),
+            Vec2::new(15.1, 0.0)
+        ));
+        assert!(contains_point(
+            &rect(10.0, 5.0),
+            at(0.0, 0.0),
+            Vec2::new(10.0, -5.0)
+        ));
+        assert!(!contains_point(
+            &rect(10.0, 5.0),
+            at(0.0, 0.0),
+            Vec2::new(0.0, 6.0)
+        ));
+
+        let turned = Isometry2d::new(Vec2::ZERO, Rot2::radians(FRAC_PI_4));
+        assert!(contains_point(
+            &rect(10.0, 10.0),
+            turned,
+            Vec2::new(0.0, 14.0)
+        ));
+        assert!(!contains_point(
+            &rect(10.0, 10.0),
+            turned,
+            Vec2::new(9.0, 9.0)
+        ));
+
+        assert!(contains_point(
+            &triangle(),
+            at(0.0, 0.0),
+            Vec2::new(0.0, 9.0)
+        ));
+        assert!(!contains_point(
+            &triangle(),
+            at(0.0, 0.0),
+            Vec2::new(8.0, 8.0)
+        ));
+    }
+}
@@ -3,7 +3,9 @@ use crate::prelude::*;
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub enum CollisionSet {
+    Broadphase,
     Detect,
+    Solve,
     EmitEvents,
 }
 
@@ -11,23 +13,49 @@ pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.configure_sets(FixedUpdate, CollisionSet::Detect.after(advance_physics))
+        app.configure_sets(PhysicsSubstep, CollisionSet::Solve.in_set(PhysicsSet::Solve))
+            .configure_sets(
+                PhysicsSubstep,
+                (CollisionSet::Broadphase, CollisionSet::Detect)
+                    .chain()
+                    .in_set(PhysicsSet::Detect),
+            )
             .configure_sets(
                 FixedUpdate,
-                CollisionSet::EmitEvents.after(CollisionSet::Detect),
+                CollisionSet::EmitEvents.in_set(PhysicsSet::EmitEvents),
             )
             // Resources and events
             .init_resource::<Contacts>()
+            .init_resource::<CollisionConfig>()
+            .init_resource::<Broadphase>()
+            .init_resource::<WallGrid>()
+            .init_resource::<ContactConstraints>()
             .add_message::<CollisionEvent>()
             // Systems
+            // Outside the sub-steps and their run condition, so spatial queries see the walls
+            // before the first step and while the simulation is paused
+            .add_systems(PreUpdate, rebuild_wall_grid)
             .add_systems(
                 FixedUpdate,
-                (circle_wall_collision_system, circle_circle_collision_system)
+                rebuild_wall_grid
+                    .after(PhysicsSet::PrePhysics)
+                    .before(PhysicsSet::Integrate),
+            )
+            .add_systems(
+                PhysicsSubstep,
+                // Walls first, since CCD can move a body back along its path
+                (static_collision_system, dynamic_collision_system)
+                    .chain()
                     .in_set(CollisionSet::Detect),
             )
+            .add_systems(
+                PhysicsSubstep,
+                solve_contacts.in_set(CollisionSet::Solve),
+            )
             .add_systems(
                 FixedUpdate,
                 emit_collision_events.in_set(CollisionSet::EmitEvents),
-            );
+            )
+            .add_observer(clear_removed_contacts);
     }
 }
@@ -1,16 +1,380 @@
+use crate::physics::*;
 use crate::prelude::*;
 use std::collections::HashMap;
 
 /// Keeps track of which pairs touched in the previous step
+///
+/// Read it as a contact graph: [`Contacts::of`], [`Contacts::touching`] and [`Contacts::pairs`]
+/// all reflect the last completed fixed step.
 #[derive(Resource, Default)]
 pub struct Contacts {
-    pub current: HashMap<(Entity, Entity), ContactData>,
-    pub prev: HashMap<(Entity, Entity), ContactData>,
+    pub(crate) current: HashMap<(Entity, Entity), ContactData>,
+    pub(crate) prev: HashMap<(Entity, Entity), ContactData>,
+    adjacency: HashMap<Entity, Vec<Entity>>, // every entity touching the key, from `prev`
 }
 
-#[derive(Clone, Copy, Default)]
+impl Contacts {
+    /// Every entity touching `entity`, with the contact seen from `entity`'s side
+    /// (the normal points towards `entity`).
+    pub fn of(&self, entity: Entity) -> impl Iterator<Item = (Entity, ContactData)> + '_ {
+        self.adjacency
+            .get(&entity)
+            .into_iter()
+            .flatten()
+            .filter_map(move |&other| Some((other, self.get(entity, other)?)))
+    }
+
+    /// Whether `a` and `b` touched in the last step.
+    pub fn touching(&self, a: Entity, b: Entity) -> bool {
+        self.prev.contains_key(&(a.min(b), a.max(b)))
+    }
+
+    /// The contact between `a` and `b`, seen from `a`'s side.
+    pub fn get(&self, a: Entity, b: Entity) -> Option<ContactData> {
+        let data = *self.prev.get(&(a.min(b), a.max(b)))?;
+        Some(if a < b { data } else { data.swapped() })
+    }
+
+    /// Every touching pair, each listed once with the data from the first entity's side.
+    pub fn pairs(&self) -> impl Iterator<Item = (Entity, Entity, &ContactData)> {
+        self.prev.iter().map(|(&(a, b), data)| (a, b, data))
+    }
+
+    /// Rebuilds the adjacency index from the settled contacts. Called whenever `prev` changes.
+    pub(crate) fn rebuild_adjacency(&mut self) {
+        self.adjacency.clear();
+        for &(a, b) in self.prev.keys() {
+            self.adjacency.entry(a).or_default().push(b);
+            self.adjacency.entry(b).or_default().push(a);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
 pub struct ContactData {
-    pub impulse: f32, // N·s magnitude of this frame’s hit
-    pub v_a_n: f32,   // A’s speed along the normal *before* the impulse
-    pub v_b_n: f32,   // B’s speed along the normal *before* the impulse
+    pub impulse: f32,          // N·s magnitude of this frame’s hit
+    pub friction_impulse: f32, // N·s magnitude of the friction applied along the surface
+    pub v_a_n: f32,            // A’s speed along the normal *before* the impulse
+    pub v_b_n: f32,            // B’s speed along the normal *before* the impulse
+    pub restitution: f32,      // Combined restitution used to resolve the hit
+    pub friction: f32,         // Combined friction coefficient used to resolve the hit
+    pub normal: Vec2,          // Unit, points from B to A
+    pub point: Vec2,           // World space
+    pub penetration: f32,      // Depth before solving
+    pub duration: f32,         // Seconds since the pair started touching
+}
+
+/// A touching pair found by the detection systems, waiting for `solve_contacts`.
+#[derive(Debug, Clone, Copy)]
+pub struct ContactConstraint {
+    pub a: Entity,
+    pub b: Entity,
+    pub b_is_static: bool, // B is a wall: no velocity, infinite mass
+    pub contact: Contact,  // normal points from B to A
+    pub material: PhysicsMaterial,
+}
+
+impl ContactConstraint {
+    pub fn new(
+        a: Entity,
+        b: Entity,
+        b_is_static: bool,
+        contact: Contact,
+        material: PhysicsMaterial,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            b_is_static,
+            contact,
+            material,
+        }
+    }
+}
+
+/// Contacts found in the current sub-step. Emptied by the solver.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct ContactConstraints(pub Vec<ContactConstraint>);
+
+impl ContactData {
+    /// The same contact seen from B, with the normal and normal speeds flipped to match.
+    pub fn swapped(self) -> Self {
+        ContactData {
+            v_a_n: -self.v_b_n,
+            v_b_n: -self.v_a_n,
+            normal: -self.normal,
+            ..self
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Resource)]
+pub struct CollisionConfig {
+    /// Cell size of the broadphase grid used for dynamic vs dynamic pairs.
+    pub broadphase_cell_size: f32,
+    /// Cell size of the static grid holding the walls.
+    pub wall_cell_size: f32,
+    /// Passes the solver makes over all contacts. More passes settle crowds better.
+    pub solver_iterations: u32,
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        CollisionConfig {
+            broadphase_cell_size: DEFAULT_BROADPHASE_CELL_SIZE,
+            wall_cell_size: DEFAULT_WALL_CELL_SIZE,
+            solver_iterations: DEFAULT_SOLVER_ITERATIONS,
+        }
+    }
+}
+
+/// Uniform grid hashing entities by the cells their bounding box overlaps.
+///
+/// Entities are remembered in insertion order, and every query returns them in that order,
+/// so callers iterating the grid see the same ordering they would get from a plain query.
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f32,
+    entities: Vec<Entity>,
+    cells: HashMap<IVec2, Vec<usize>>,
+}
+
+impl SpatialHash {
+    /// An empty grid, usable right away without a [`SpatialHash::clear`] first.
+    pub fn new(cell_size: f32) -> Self {
+        SpatialHash {
+            cell_size: cell_size.max(f32::EPSILON),
+            entities: Vec::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Empties the grid. Buckets used since the last clear keep their allocation,
+    /// buckets that stayed empty are dropped so the map doesn't grow forever.
+    pub fn clear(&mut self, cell_size: f32) {
+        self.cell_size = cell_size.max(f32::EPSILON);
+        self.entities.clear();
+        self.cells.retain(|_, bucket| {
+            let used = !bucket.is_empty();
+            bucket.clear();
+            used
+        });
+    }
+
+    pub fn insert(&mut self, entity: Entity, center: Vec2, half_extents: Vec2) {
+        let idx = self.entities.len();
+        self.entities.push(entity);
+
+        let (min, max) = self.cell_range(center, half_extents);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(idx);
+            }
+        }
+    }
+
+    /// Every pair of entities sharing at least one cell, each pair reported once.
+    /// Pairs are sorted the same way `iter_combinations` would yield them.
+    pub fn candidate_pairs(&self) -> Vec<(Entity, Entity)> {
+        let mut pairs = Vec::new();
+        for bucket in self.cells.values() {
+            for (i, &a) in bucket.iter().enumerate() {
+                for &b in &bucket[i + 1..] {
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        pairs
+            .into_iter()
+            .map(|(a, b)| (self.entities[a], self.entities[b]))
+            .collect()
+    }
+
+    /// Every entity whose cells overlap the given box, each reported once in insertion order.
+    pub fn query(&self, center: Vec2, half_extents: Vec2) -> Vec<Entity> {
+        let (min, max) = self.cell_range(center, half_extents);
+        let mut hits = Vec::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                if let Some(bucket) = self.cells.get(&IVec2::new(x, y)) {
+                    hits.extend_from_slice(bucket);
+                }
+            }
+        }
+        self.ordered(hits)
+    }
+
+    /// Every entity whose cells come within `radius` of the segment, each reported once in
+    /// insertion order.
+    ///
+    /// Walks only the cells the segment crosses (plus `radius` worth of neighbours),
+    /// so a long diagonal cast doesn't visit the whole box around it.
+    pub fn query_segment(&self, start: Vec2, end: Vec2, radius: f32) -> Vec<Entity> {
+        let pad = (radius.max(0.0) / self.cell_size).ceil() as i32;
+        let mut hits = Vec::new();
+        let mut visit = |cell: IVec2| {
+            for y in -pad..=pad {
+                for x in -pad..=pad {
+                    if let Some(bucket) = self.cells.get(&(cell + IVec2::new(x, y))) {
+                        hits.extend_from_slice(bucket);
+                    }
+                }
+            }
+        };
+
+        let from = start / self.cell_size;
+        let to = end / self.cell_size;
+        let delta = to - from;
+        let mut cell = from.floor().as_ivec2();
+        let last = to.floor().as_ivec2();
+        let step = IVec2::new(sign(delta.x), sign(delta.y));
+
+        // Fraction of the segment needed to cross one cell, and to reach the first boundary
+        let t_delta = delta.abs().recip();
+        let boundary = cell.as_vec2() + step.max(IVec2::ZERO).as_vec2();
+        let mut t_max = Vec2::select(
+            step.cmpeq(IVec2::ZERO),
+            Vec2::INFINITY,
+            (boundary - from) / delta,
+        );
+
+        visit(cell);
+        // Counting the steps keeps rounding from walking past the last cell
+        for _ in 0..(last - cell).abs().element_sum() {
+            if cell.y == last.y || (cell.x != last.x && t_max.x < t_max.y) {
+                cell.x += step.x;
+                t_max.x += t_delta.x;
+            } else {
+                cell.y += step.y;
+                t_max.y += t_delta.y;
+            }
+            visit(cell);
+        }
+        self.ordered(hits)
+    }
+
+    fn ordered(&self, mut hits: Vec<usize>) -> Vec<Entity> {
+        hits.sort_unstable();
+        hits.dedup();
+
+        hits.into_iter().map(|i| self.entities[i]).collect()
+    }
+
+    fn cell_range(&self, center: Vec2, half_extents: Vec2) -> (IVec2, IVec2) {
+        let min = ((center - half_extents) / self.cell_size).floor().as_ivec2();
+        let max = ((center + half_extents) / self.cell_size).floor().as_ivec2();
+        (min, max)
+    }
+}
+
+fn sign(x: f32) -> i32 {
+    if x > 0.0 {
+        1
+    } else if x < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Broadphase for dynamic colliders, rebuilt every fixed step.
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct Broadphase(pub SpatialHash);
+
+impl Default for Broadphase {
+    fn default() -> Self {
+        Broadphase(SpatialHash::new(DEFAULT_BROADPHASE_CELL_SIZE))
+    }
+}
+
+/// Acceleration grid for the static walls.
+/// Only rebuilt when a wall is added, removed or changed, since walls never move on their own.
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct WallGrid(pub SpatialHash);
+
+impl Default for WallGrid {
+    fn default() -> Self {
+        WallGrid(SpatialHash::new(DEFAULT_WALL_CELL_SIZE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entities(n: u32) -> Vec<Entity> {
+        (0..n)
+            .map(|i| Entity::from_raw_u32(i + 1).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn only_neighbours_become_candidate_pairs() {
+        let es = entities(4);
+        let mut hash = SpatialHash::new(100.0);
+        hash.insert(es[0], Vec2::new(0.0, 0.0), Vec2::splat(50.0));
+        hash.insert(es[1], Vec2::new(90.0, 0.0), Vec2::splat(50.0));
+        hash.insert(es[2], Vec2::new(1000.0, 0.0), Vec2::splat(50.0));
+        hash.insert(es[3], Vec2::new(-60.0, -60.0), Vec2::splat(50.0));
+
+        let pairs = hash.candidate_pairs();
+        assert!(pairs.contains(&(es[0], es[1])));
+        assert!(pairs.contains(&(es[0], es[3])));
+        assert!(!pairs.iter().any(|&(a, b)| a == es[2] || b == es[2]));
+        let mut deduped = pairs.clone();
+        deduped.dedup();
+        assert_eq!(pairs, deduped);
+    }
+
+    #[test]
+    fn queries_return_each_entity_once_in_insertion_order() {
+        let es = entities(3);
+        let mut hash = SpatialHash::new(10.0);
+        hash.insert(es[0], Vec2::new(50.0, 0.0), Vec2::splat(30.0));
+        hash.insert(es[1], Vec2::new(0.0, 0.0), Vec2::splat(30.0));
+        hash.insert(es[2], Vec2::new(500.0, 0.0), Vec2::splat(1.0));
+
+        assert_eq!(
+            hash.query(Vec2::new(25.0, 0.0), Vec2::splat(5.0)),
+            vec![es[0], es[1]]
+        );
+        assert_eq!(hash.query(Vec2::new(500.0, 0.0), Vec2::ZERO), vec![es[2]]);
+
+        hash.clear(10.0);
+        assert!(
+            hash.query(Vec2::new(25.0, 0.0), Vec2::splat(5.0))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn segment_queries_only_walk_the_cells_they_cross() {
+        let es = entities(4);
+        let mut hash = SpatialHash::new(10.0);
+        hash.insert(es[0], Vec2::new(95.0, 95.0), Vec2::splat(1.0));
+        hash.insert(es[1], Vec2::new(95.0, 5.0), Vec2::splat(1.0));
+        hash.insert(es[2], Vec2::new(-45.0, 55.0), Vec2::splat(1.0));
+        hash.insert(es[3], Vec2::new(55.0, 35.0), Vec2::splat(1.0));
+
+        // The box around the diagonal holds everything but the segment only crosses one end
+        let diagonal = hash.query_segment(Vec2::ZERO, Vec2::splat(99.0), 0.0);
+        assert_eq!(diagonal, vec![es[0]]);
+        assert_eq!(
+            hash.query_segment(Vec2::splat(99.0), Vec2::ZERO, 0.0),
+            diagonal
+        );
+        // A radius reaches the neighbouring cells too
+        assert_eq!(
+            hash.query_segment(Vec2::ZERO, Vec2::splat(99.0), 5.0),
+            vec![es[0], es[3]]
+        );
+        assert_eq!(
+            hash.query_segment(Vec2::new(-45.0, 0.0), Vec2::new(-45.0, 100.0), 0.0),
+            vec![es[2]]
+        );
+    }
 }
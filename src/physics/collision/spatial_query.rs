@@ -0,0 +1,159 @@
+use crate::physics::*;
+use crate::prelude::*;
+use bevy::ecs::query::QueryData;
+use bevy::ecs::system::SystemParam;
+
+/// First collider hit by a cast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CastHit {
+    pub entity: Entity,
+    pub distance: f32, // how far the origin travelled before the hit
+    pub point: Vec2,   // world space, on the surface of the hit collider
+    pub normal: Vec2,  // surface normal at `point`, pointing out of the hit collider
+}
+
+/// Any collider, placed by its physical state if it has one, by its `Transform` otherwise.
+#[derive(QueryData)]
+pub struct QueryCollider {
+    entity: Entity,
+    collider: &'static Collider,
+    transform: Option<&'static Transform>,
+    pos: Option<&'static PhysicalTranslation>,
+    rot: Option<&'static PhysicalRotation>,
+}
+
+impl QueryColliderItem<'_, '_> {
+    fn isometry(&self) -> Isometry2d {
+        let mut iso = self
+            .transform
+            .map_or(Isometry2d::IDENTITY, transform_isometry);
+        if let Some(pos) = self.pos {
+            iso.translation = pos.truncate();
+        }
+        if let Some(rot) = self.rot {
+            iso.rotation = rot.rot2();
+        }
+        iso
+    }
+}
+
+/// Ray casts, shape casts and overlap tests against every [`Collider`], walls and movers alike.
+/// [`AreaEffector`] regions are left out, so wind or mud never blocks a line of sight.
+///
+/// Walls are looked up through the [`WallGrid`], so only those in the cells a cast crosses
+/// or an overlap covers are tested, and a new wall is found once the next physics step has
+/// added it to the grid. Every other collider is tested one by one on each call,
+/// so their cost grows with how many movers there are.
+///
+/// Entities in `exclude` are skipped, e.g. the caster itself.
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, 's> {
+    walls: Query<'w, 's, QueryCollider, (WallFilter, Without<AreaEffector>)>,
+    others: Query<'w, 's, QueryCollider, (NonWallFilter, Without<AreaEffector>)>,
+    grid: Res<'w, WallGrid>,
+}
+
+impl SpatialQuery<'_, '_> {
+    /// First collider along the ray, within `max_distance`.
+    /// A ray starting inside a collider hits it at distance 0.
+    pub fn cast_ray(
+        &self,
+        origin: Vec2,
+        direction: Dir2,
+        max_distance: f32,
+        exclude: &[Entity],
+    ) -> Option<CastHit> {
+        self.cast_circle(origin, 0.0, direction, max_distance, exclude)
+    }
+
+    /// First collider touched by a circle moving along the ray, within `max_distance`.
+    /// A circle starting in overlap with a collider hits it at distance 0.
+    pub fn cast_circle(
+        &self,
+        origin: Vec2,
+        radius: f32,
+        direction: Dir2,
+        max_distance: f32,
+        exclude: &[Entity],
+    ) -> Option<CastHit> {
+        let end = origin + direction * max_distance;
+        let circle = ColliderShape::Circle { radius };
+
+        let walls = self.grid.query_segment(origin, end, radius);
+        let mut first: Option<CastHit> = None;
+        for item in self.included(walls, exclude) {
+            let iso = item.isometry();
+
+            let hit = if let Some(contact) = collide(
+                &circle,
+                Isometry2d::from_translation(origin),
+                &item.collider.0,
+                iso,
+            ) {
+                CastHit {
+                    entity: item.entity,
+                    distance: 0.0,
+                    point: origin - contact.normal * radius,
+                    normal: contact.normal,
+                }
+            } else if contains_point(&item.collider.0, iso, origin) {
+                CastHit {
+                    entity: item.entity,
+                    distance: 0.0,
+                    point: origin,
+                    normal: -*direction,
+                }
+            } else if let Some(toi) = sweep_circle(origin, end, radius, &item.collider.0, iso) {
+                CastHit {
+                    entity: item.entity,
+                    distance: toi.t * max_distance,
+                    point: origin + direction * (toi.t * max_distance) - toi.normal * radius,
+                    normal: toi.normal,
+                }
+            } else {
+                continue;
+            };
+
+            if first.is_none_or(|best| hit.distance < best.distance) {
+                first = Some(hit);
+            }
+        }
+        first
+    }
+
+    /// Every collider containing `point`.
+    pub fn overlap_point(&self, point: Vec2, exclude: &[Entity]) -> Vec<Entity> {
+        let walls = self.grid.query(point, Vec2::ZERO);
+        self.included(walls, exclude)
+            .filter(|item| contains_point(&item.collider.0, item.isometry(), point))
+            .map(|item| item.entity)
+            .collect()
+    }
+
+    /// Every collider overlapping the circle.
+    pub fn overlap_circle(&self, center: Vec2, radius: f32, exclude: &[Entity]) -> Vec<Entity> {
+        let circle = ColliderShape::Circle { radius };
+        let circle_iso = Isometry2d::from_translation(center);
+        let walls = self.grid.query(center, Vec2::splat(radius));
+        self.included(walls, exclude)
+            .filter(|item| {
+                collide(&circle, circle_iso, &item.collider.0, item.isometry()).is_some()
+                    || contains_point(&item.collider.0, item.isometry(), center)
+            })
+            .map(|item| item.entity)
+            .collect()
+    }
+
+    /// The given walls from the grid, then every other collider, minus `exclude`.
+    fn included<'a>(
+        &'a self,
+        walls: Vec<Entity>,
+        exclude: &'a [Entity],
+    ) -> impl Iterator<Item = QueryColliderItem<'a, 'a>> + 'a {
+        walls
+            .into_iter()
+            .filter_map(move |wall| self.walls.get(wall).ok())
+            .chain(self.others.iter())
+            .filter(move |item| !exclude.contains(&item.entity))
+    }
+}
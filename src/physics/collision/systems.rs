@@ -1,125 +1,431 @@
 use crate::physics::*;
 use crate::prelude::*;
+use bevy::ecs::query::QueryData;
+use bevy::ecs::system::SystemParam;
 use std::collections::HashMap;
 use std::mem;
 
-#[derive(Clone, Copy)]
-struct Contact {
-    normal: Vec2, // points *from* B to A
-    penetration: f32,
+/// Everything the collision systems read or write on a dynamic body.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct CollisionBody {
+    entity: Entity,
+    pos: &'static mut PhysicalTranslation,
+    prev_pos: &'static PreviousPhysicalTranslation,
+    vel: &'static mut Velocity,
+    rot: Option<&'static PhysicalRotation>,
+    ang_vel: Option<&'static mut AngularVelocity>,
+    collider: &'static Collider,
+    mass: &'static Mass,
+    kind: Option<&'static RigidBody>,
+    material: Option<&'static PhysicsMaterial>,
+    layers: Option<&'static CollisionLayers>,
+    ccd: Has<Ccd>,
+    sensor: Has<Sensor>,
+    sleeping: Has<Sleeping>,
 }
 
-/// System for circle (dynamic) vs rectangle (static wall) collisions.
-pub fn circle_wall_collision_system(
-    mut movers: Query<
-        (
-            Entity,
-            &mut PhysicalTranslation,
-            &mut Velocity,
-            &Collider,
-            &Mass,
-        ),
-        With<Velocity>,
-    >,
-    walls: Query<(Entity, &Transform, &Collider), Without<Velocity>>,
+/// Impulses the solver actually applied, in N·s.
+#[derive(Clone, Copy, Default)]
+struct AppliedImpulse {
+    normal: f32,
+    tangent: f32, // friction, signed along the normal's perpendicular
+}
+
+/// Static colliders taking part in wall collisions: walls, static sensors and any other
+/// body that has no `Velocity` to move with.
+pub(crate) type WallFilter = (
+    Or<(With<Wall>, With<Sensor>, With<RigidBody>)>,
+    Without<Velocity>,
+);
+/// Every collider [`WallFilter`] leaves out: movers and colliders without any body.
+pub(crate) type NonWallFilter = Or<(
+    With<Velocity>,
+    (Without<Wall>, Without<Sensor>, Without<RigidBody>),
+)>;
+type WallQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static Collider,
+        Option<&'static PhysicsMaterial>,
+        Option<&'static CollisionLayers>,
+        Has<Sensor>,
+    ),
+    WallFilter,
+>;
+type ChangedWallFilter = (
+    WallFilter,
+    Or<(
+        Changed<Transform>,
+        Changed<Collider>,
+        Added<Wall>,
+        Added<Sensor>,
+        Added<RigidBody>,
+    )>,
+);
+
+/// Removals that can take a collider out of [`WallFilter`] or put it in.
+#[derive(SystemParam)]
+pub struct RemovedWallParts<'w, 's> {
+    walls: RemovedComponents<'w, 's, Wall>,
+    colliders: RemovedComponents<'w, 's, Collider>,
+    sensors: RemovedComponents<'w, 's, Sensor>,
+    bodies: RemovedComponents<'w, 's, RigidBody>,
+    // Losing its `Velocity` turns a collider into a wall
+    velocities: RemovedComponents<'w, 's, Velocity>,
+}
+
+impl RemovedWallParts<'_, '_> {
+    /// Whether anything was removed since the last call.
+    /// Drains every reader so stale removals don't trigger a rebuild next time.
+    fn any(&mut self) -> bool {
+        self.walls.read().count()
+            + self.colliders.read().count()
+            + self.sensors.read().count()
+            + self.bodies.read().count()
+            + self.velocities.read().count()
+            > 0
+    }
+}
+
+/// Rebuilds the [`WallGrid`] whenever a wall is added, removed or changed.
+///
+/// Walls never move on their own, so on most steps this is a no-op.
+pub fn rebuild_wall_grid(
+    walls: Query<(Entity, &Transform, &Collider), WallFilter>,
+    changed: Query<(), ChangedWallFilter>,
+    mut removed: RemovedWallParts,
+    cfg: Res<CollisionConfig>,
+    mut grid: ResMut<WallGrid>,
+) {
+    if !removed.any() && changed.is_empty() && !cfg.is_changed() {
+        return;
+    }
+
+    grid.clear(cfg.wall_cell_size);
+    for (wall, tf, col) in &walls {
+        let iso = transform_isometry(tf);
+        grid.insert(
+            wall,
+            iso.translation,
+            col.rotated_half_extents(iso.rotation),
+        );
+    }
+}
+
+/// Finds the contacts between dynamic bodies and static walls, for any pair of shapes.
+///
+/// Only walls sharing a [`WallGrid`] cell with the mover, and whose [`CollisionLayers`] match, are tested.
+/// Circles with [`Ccd`] first sweep their whole step so they can't tunnel through thin walls.
+pub fn static_collision_system(
+    mut movers: Query<CollisionBody, With<Velocity>>,
+    walls: WallQuery,
+    grid: Res<WallGrid>,
+    mut constraints: ResMut<ContactConstraints>,
     mut contacts: ResMut<Contacts>,
 ) {
-    for (mover, mut pos, mut vel, col, m) in &mut movers {
-        let ColliderShape::Circle { radius } = col.0 else {
+    for mut body in &mut movers {
+        // Kinematic and static bodies don't react to walls, sleeping ones can't have moved
+        if !body.is_dynamic() || body.sleeping {
             continue;
-        };
-        let center = Vec2::new(pos.x, pos.y);
+        }
+        let mat = body.material.copied().unwrap_or_default();
 
-        for (wall, tf, wall_col) in &walls {
-            let ColliderShape::Rect { half_extents } = wall_col.0 else {
+        // Sensors never get stopped, so there is nothing to sweep for
+        if let (true, false, ColliderShape::Circle { radius }) =
+            (body.ccd, body.sensor, body.collider.0)
+        {
+            let start = body.prev_pos.truncate();
+            let end = body.pos.truncate();
+
+            if let Some((wall, toi, wall_mat)) =
+                first_wall_hit(start, end, radius, body.layers, &walls, &grid)
+            {
+                // Rewind to the first impact, leaving a small gap so the discrete pass below
+                // doesn't pick the same wall up again.
+                let hit_center = start + (end - start) * toi.t + toi.normal * CCD_SKIN;
+                body.pos.x = hit_center.x;
+                body.pos.y = hit_center.y;
+
+                constraints.push(ContactConstraint::new(
+                    body.entity,
+                    wall,
+                    true,
+                    Contact {
+                        normal: toi.normal,
+                        penetration: 0.0,
+                        point: hit_center - toi.normal * radius,
+                    },
+                    mat.combine(&wall_mat),
+                ));
+            }
+        }
+
+        let iso = body.isometry();
+        let half_extents = body.collider.rotated_half_extents(iso.rotation);
+        for wall in grid.query(iso.translation, half_extents) {
+            let Ok((tf, wall_col, wall_mat, wall_layers, wall_sensor)) = walls.get(wall) else {
                 continue;
             };
-            let rect_pos = tf.translation.truncate();
+            if !layers_interact(body.layers, wall_layers) {
+                continue;
+            }
 
-            if let Some(contact) = circle_vs_rect(center, radius, rect_pos, half_extents) {
-                let data = circle_wall_contact_data(vel.0.truncate(), m.0, &contact);
-                resolve_circle_wall(pos.reborrow(), vel.reborrow(), contact, RESTITUTION);
-                contacts.current.insert(ordered_pair(mover, wall), data);
+            if let Some(contact) =
+                collide(&body.collider.0, iso, &wall_col.0, transform_isometry(tf))
+            {
+                let combined = mat.combine(&wall_mat.copied().unwrap_or_default());
+                if body.sensor || wall_sensor {
+                    let data = contact_data(
+                        body.vel.truncate(),
+                        Vec2::ZERO,
+                        &contact,
+                        AppliedImpulse::default(),
+                        &combined,
+                    );
+                    record_contact(&mut contacts, body.entity, wall, data);
+                } else {
+                    constraints.push(ContactConstraint::new(
+                        body.entity,
+                        wall,
+                        true,
+                        contact,
+                        combined,
+                    ));
+                }
             }
         }
     }
 }
 
-/// System for circle vs circle collisions (between dynamic moving entities).
-pub fn circle_circle_collision_system(
-    mut q: Query<(
-        Entity,
-        &mut PhysicalTranslation,
-        &mut Velocity,
-        &Collider,
-        &Mass,
-    )>,
+/// Finds the contacts between dynamic moving entities, for any pair of shapes.
+///
+/// Candidate pairs come from the [`Broadphase`] grid, which is rebuilt here every step,
+/// so only colliders sharing a cell and whose [`CollisionLayers`] match reach the narrowphase.
+/// Pairs where neither side is awake are skipped too.
+pub fn dynamic_collision_system(
+    mut q: Query<CollisionBody>,
+    cfg: Res<CollisionConfig>,
+    mut broadphase: ResMut<Broadphase>,
+    mut constraints: ResMut<ContactConstraints>,
     mut contacts: ResMut<Contacts>,
 ) {
-    let mut combos = q.iter_combinations_mut::<2>();
-    while let Some(
-        [
-            (e1, mut p1, mut v1, col1, m1),
-            (e2, mut p2, mut v2, col2, m2),
-        ],
-    ) = combos.fetch_next()
-    {
-        let ColliderShape::Circle { radius: r1 } = col1.0 else {
+    broadphase.clear(cfg.broadphase_cell_size);
+    for body in &q {
+        let rotation = body.rot.map_or(Rot2::IDENTITY, PhysicalRotation::rot2);
+        let iso = Isometry2d::new(body.pos.truncate(), rotation);
+        let half_extents = body.collider.rotated_half_extents(iso.rotation);
+        broadphase.insert(body.entity, iso.translation, half_extents);
+    }
+
+    for (e1, e2) in broadphase.candidate_pairs() {
+        let Ok([b1, b2]) = q.get_many_mut([e1, e2]) else {
             continue;
         };
-        let ColliderShape::Circle { radius: r2 } = col2.0 else {
+        if !layers_interact(b1.layers, b2.layers)
+            || !(b1.is_dynamic() || b2.is_dynamic())
+            || !(b1.is_awake() || b2.is_awake())
+        {
             continue;
-        };
+        }
 
-        if let Some(contact) =
-            circle_vs_circle(Vec2::new(p1.x, p1.y), r1, Vec2::new(p2.x, p2.y), r2)
-        {
-            let data =
-                circle_circle_contact_data(v1.0.truncate(), v2.0.truncate(), m1.0, m2.0, &contact);
-
-            resolve_circle_circle(
-                p1.reborrow(),
-                v1.reborrow(),
-                m1.0,
-                p2.reborrow(),
-                v2.reborrow(),
-                m2.0,
-                contact,
-                RESTITUTION,
-            );
+        let hit = collide(&b1.collider.0, b1.isometry(), &b2.collider.0, b2.isometry());
+        if let Some(contact) = hit {
+            let combined = b1
+                .material
+                .copied()
+                .unwrap_or_default()
+                .combine(&b2.material.copied().unwrap_or_default());
+
+            if b1.sensor || b2.sensor {
+                let (u1, u2) = (b1.vel.truncate(), b2.vel.truncate());
+                let data = contact_data(u1, u2, &contact, AppliedImpulse::default(), &combined);
+                record_contact(&mut contacts, e1, e2, data);
+            } else {
+                constraints.push(ContactConstraint::new(e1, e2, false, contact, combined));
+            }
+        }
+    }
+}
 
-            contacts.current.insert(ordered_pair(e1, e2), data);
+/// Resolves every contact found this sub-step with sequential impulses.
+///
+/// Each iteration revisits all contacts using the velocities left by the previous ones,
+/// so pushing one pair apart doesn't leave its neighbours approaching or overlapping.
+/// Impulses are accumulated per contact and only the total is clamped, so the solver can
+/// take back an impulse that turned out too large, but never pulls two bodies together.
+pub fn solve_contacts(
+    mut q: Query<CollisionBody>,
+    cfg: Res<CollisionConfig>,
+    mut constraints: ResMut<ContactConstraints>,
+    mut contacts: ResMut<Contacts>,
+) {
+    let mut solver: Vec<SolverContact> = constraints
+        .drain(..)
+        .filter_map(|constraint| {
+            let a = q.get(constraint.a).ok()?;
+            let (start_b, v_b, w_b) = if constraint.b_is_static {
+                (Vec2::ZERO, Vec2::ZERO, 0.0)
+            } else {
+                let b = q.get(constraint.b).ok()?;
+                let w_b = b.ang_vel.map_or(0.0, |w| w.0);
+                (b.pos.truncate(), b.vel.truncate(), w_b)
+            };
+            let (start_a, v_a) = (a.pos.truncate(), a.vel.truncate());
+            let w_a = a.ang_vel.map_or(0.0, |w| w.0);
+
+            let point = constraint.contact.point;
+            let (r_a, r_b) = (point - start_a, point - start_b);
+            let rel = v_a + w_a * r_a.perp() - (v_b + w_b * r_b.perp());
+            let approach = rel.dot(constraint.contact.normal).min(0.0);
+
+            Some(SolverContact {
+                constraint,
+                r_a,
+                r_b,
+                start_a,
+                start_b,
+                v_a,
+                v_b,
+                target_speed: -constraint.material.restitution * approach,
+                impulse: AppliedImpulse::default(),
+            })
+        })
+        .collect();
+
+    for _ in 0..cfg.solver_iterations {
+        for contact in &mut solver {
+            contact.with_bodies(&mut q, SolverContact::solve_velocity);
         }
     }
+    for _ in 0..cfg.solver_iterations {
+        for contact in &mut solver {
+            contact.with_bodies(&mut q, SolverContact::solve_position);
+        }
+    }
+
+    for contact in &solver {
+        let c = &contact.constraint;
+        let data = contact_data(
+            contact.v_a,
+            contact.v_b,
+            &c.contact,
+            contact.impulse,
+            &c.material,
+        );
+        record_contact(&mut contacts, c.a, c.b, data);
+    }
 }
 
+/// Compares this step's contacts with the last one's, writing [`CollisionEvent`]s and
+/// triggering [`CollisionStart`] / [`CollisionEnd`] on the entities involved.
+///
+/// Pairs between bodies that are all asleep or static weren't tested this step,
+/// so they keep touching until one side wakes up.
 pub fn emit_collision_events(
+    clock: PhysicsClock,
     mut contacts: ResMut<Contacts>,
     mut writer: MessageWriter<CollisionEvent>,
+    mut commands: Commands,
+    bodies: Query<(Has<Sleeping>, Option<&RigidBody>), With<Movable>>,
 ) {
-    let current: HashMap<(Entity, Entity), ContactData> = mem::take(&mut contacts.current);
+    let mut current: HashMap<(Entity, Entity), ContactData> = mem::take(&mut contacts.current);
+    let awake = |entity: Entity| {
+        bodies.get(entity).is_ok_and(|(sleeping, kind)| {
+            !sleeping && kind.is_none_or(|kind| *kind != RigidBody::Static)
+        })
+    };
+    for (&pair, data) in &contacts.prev {
+        if !current.contains_key(&pair) && !awake(pair.0) && !awake(pair.1) {
+            current.insert(
+                pair,
+                ContactData {
+                    impulse: 0.0,
+                    friction_impulse: 0.0,
+                    ..*data
+                },
+            );
+        }
+    }
 
-    for (&pair, data) in &current {
-        if !contacts.prev.contains_key(&pair) {
+    for (&pair, data) in &mut current {
+        let Some(prev) = contacts.prev.get(&pair) else {
             writer.write(CollisionEvent::Started {
                 a: pair.0,
                 b: pair.1,
                 impulse: data.impulse,
                 v_a_n: data.v_a_n,
                 v_b_n: data.v_b_n,
+                normal: data.normal,
+                point: data.point,
+                penetration: data.penetration,
+                duration: data.duration,
             });
-        }
+            trigger_start(&mut commands, pair, *data);
+            continue;
+        };
+
+        data.duration = prev.duration + clock.delta_secs();
+        writer.write(CollisionEvent::Persisting {
+            a: pair.0,
+            b: pair.1,
+            impulse: data.impulse,
+            normal: data.normal,
+            point: data.point,
+            penetration: data.penetration,
+            duration: data.duration,
+        });
     }
-    for pair in contacts.prev.keys() {
+    for (pair, data) in &contacts.prev {
         if !current.contains_key(pair) {
             writer.write(CollisionEvent::Stopped {
                 a: pair.0,
                 b: pair.1,
+                normal: data.normal,
+                point: data.point,
+                penetration: data.penetration,
+                duration: data.duration,
+                reason: StopReason::Separated,
             });
+            trigger_end(&mut commands, *pair, *data, StopReason::Separated);
         }
     }
 
     contacts.prev = current;
+    contacts.rebuild_adjacency();
+}
+
+/// Forgets every pair involving a collider as soon as it's despawned or loses its [`Collider`],
+/// emitting `Stopped` for the pairs that had already started.
+pub fn clear_removed_contacts(
+    remove: On<Remove, Collider>,
+    mut contacts: ResMut<Contacts>,
+    mut writer: MessageWriter<CollisionEvent>,
+    mut commands: Commands,
+) {
+    let removed = remove.entity;
+    let involved = |pair: &(Entity, Entity)| pair.0 == removed || pair.1 == removed;
+
+    contacts.current.retain(|pair, _| !involved(pair));
+    contacts.prev.retain(|pair, data| {
+        if !involved(pair) {
+            return true;
+        }
+        writer.write(CollisionEvent::Stopped {
+            a: pair.0,
+            b: pair.1,
+            normal: data.normal,
+            point: data.point,
+            penetration: data.penetration,
+            duration: data.duration,
+            reason: StopReason::Despawned,
+        });
+        trigger_end(&mut commands, *pair, *data, StopReason::Despawned);
+        false
+    });
+    contacts.rebuild_adjacency();
 }
 
 //--------------------------------------------------
@@ -127,156 +433,285 @@ pub fn emit_collision_events(
 //--------------------------------------------------
 
 fn ordered_pair(a: Entity, b: Entity) -> (Entity, Entity) {
-    if a.index() < b.index() {
+    if a < b {
         (a, b)
     } else {
         (b, a)
     }
 }
 
-fn circle_vs_rect(circle_pos: Vec2, radius: f32, rect_pos: Vec2, half: Vec2) -> Option<Contact> {
-    // closest point on the rectangle to the circle centre
-    let delta = circle_pos - rect_pos;
-    let clamped = delta.clamp(-half, half);
-    let closest = rect_pos + clamped;
+/// Triggers [`CollisionStart`] on both entities of the pair, each with the data from its side.
+fn trigger_start(commands: &mut Commands, (a, b): (Entity, Entity), data: ContactData) {
+    commands.trigger(CollisionStart {
+        entity: a,
+        other: b,
+        data,
+    });
+    commands.trigger(CollisionStart {
+        entity: b,
+        other: a,
+        data: data.swapped(),
+    });
+}
 
-    let diff = circle_pos - closest;
-    let dist_sq = diff.length_squared();
-    if dist_sq > radius * radius {
-        return None; // no overlap
-    }
+/// Triggers [`CollisionEnd`] on both entities of the pair, each with the data from its side.
+fn trigger_end(
+    commands: &mut Commands,
+    (a, b): (Entity, Entity),
+    data: ContactData,
+    reason: StopReason,
+) {
+    commands.trigger(CollisionEnd {
+        entity: a,
+        other: b,
+        data,
+        reason,
+    });
+    commands.trigger(CollisionEnd {
+        entity: b,
+        other: a,
+        data: data.swapped(),
+        reason,
+    });
+}
 
-    let dist = dist_sq.sqrt();
-    let normal = if dist != 0.0 {
-        diff / dist
-    } else if delta.x.abs() > delta.y.abs() {
-        Vec2::new(delta.x.signum(), 0.0)
-    } else {
-        Vec2::new(0.0, delta.y.signum())
-    };
-    Some(Contact {
-        normal,
-        penetration: radius - dist,
-    })
+/// Stores `data`, measured from `a` to `b`, under the ordered pair key.
+/// If the key swaps the two entities, the normal and normal speeds are flipped to match.
+///
+/// A pair touching again in a later sub-step adds its impulses to the first contact's.
+fn record_contact(contacts: &mut Contacts, a: Entity, b: Entity, data: ContactData) {
+    let pair = ordered_pair(a, b);
+    let data = if pair.0 == a { data } else { data.swapped() };
+    contacts
+        .current
+        .entry(pair)
+        .and_modify(|first| {
+            first.impulse += data.impulse;
+            first.friction_impulse += data.friction_impulse;
+        })
+        .or_insert(data);
 }
 
-fn circle_vs_circle(p1: Vec2, r1: f32, p2: Vec2, r2: f32) -> Option<Contact> {
-    let diff = p2 - p1;
-    let dist_sq = diff.length_squared();
-    let r_sum = r1 + r2;
-    if dist_sq >= r_sum * r_sum {
-        return None;
-    }
-    let dist = dist_sq.sqrt();
-    let normal = if dist > 0.0 {
-        diff / dist
-    } else {
-        Vec2::ONE.normalize()
-    };
-    Some(Contact {
-        normal,
-        penetration: r_sum - dist,
-    })
+/// Missing layers behave like [`CollisionLayers::default`].
+fn layers_interact(a: Option<&CollisionLayers>, b: Option<&CollisionLayers>) -> bool {
+    a.copied()
+        .unwrap_or_default()
+        .interacts_with(&b.copied().unwrap_or_default())
 }
 
-fn resolve_circle_wall(
-    mut circle_pos: Mut<PhysicalTranslation>,
-    mut circle_vel: Mut<Velocity>,
-    contact: Contact,
-    restitution: f32,
-) {
-    // Split velocity into normal & tangential parts
-    let vel2d = Vec2::new(circle_vel.x, circle_vel.y);
-    let v_n = vel2d.dot(contact.normal);
-
-    // Only flip if we were moving into the wall
-    if v_n < 0.0 {
-        // v' = v - (1 + e) * (v·n) * n
-        let reflected = vel2d - (1.0 + restitution) * v_n * contact.normal;
-        circle_vel.x = reflected.x;
-        circle_vel.y = reflected.y;
+/// Walls and other static colliders are placed by their `Transform`,
+/// only the rotation around z matters.
+pub(crate) fn transform_isometry(tf: &Transform) -> Isometry2d {
+    let (_, _, angle) = tf.rotation.to_euler(EulerRot::XYZ);
+    Isometry2d::new(tf.translation.truncate(), Rot2::radians(angle))
+}
+
+/// Earliest wall hit along a circle's path this step.
+fn first_wall_hit(
+    start: Vec2,
+    end: Vec2,
+    radius: f32,
+    layers: Option<&CollisionLayers>,
+    walls: &WallQuery,
+    grid: &WallGrid,
+) -> Option<(Entity, TimeOfImpact, PhysicsMaterial)> {
+    let sweep_center = (start + end) * 0.5;
+    let sweep_half = (end - start).abs() * 0.5 + Vec2::splat(radius);
+
+    let mut first: Option<(Entity, TimeOfImpact, PhysicsMaterial)> = None;
+    for wall in grid.query(sweep_center, sweep_half) {
+        let Ok((tf, wall_col, wall_mat, wall_layers, wall_sensor)) = walls.get(wall) else {
+            continue;
+        };
+        if wall_sensor || !layers_interact(layers, wall_layers) {
+            continue;
+        }
+
+        if let Some(toi) = sweep_circle(start, end, radius, &wall_col.0, transform_isometry(tf))
+            && first.is_none_or(|(_, best, _)| toi.t < best.t)
+        {
+            first = Some((wall, toi, wall_mat.copied().unwrap_or_default()));
+        }
     }
+    first
+}
 
-    circle_pos.x += contact.normal.x * contact.penetration;
-    circle_pos.y += contact.normal.y * contact.penetration;
+/// A contact being solved, with everything that stays fixed across the iterations.
+struct SolverContact {
+    constraint: ContactConstraint,
+    r_a: Vec2, // contact point relative to A at the start of the solve
+    r_b: Vec2,
+    start_a: Vec2,
+    start_b: Vec2,
+    v_a: Vec2, // linear velocities before solving, reported in `ContactData`
+    v_b: Vec2,
+    target_speed: f32,       // separating speed restitution aims for
+    impulse: AppliedImpulse, // accumulated over the iterations
 }
 
-#[allow(clippy::too_many_arguments)]
-fn resolve_circle_circle(
-    mut pos1: Mut<PhysicalTranslation>,
-    mut vel1: Mut<Velocity>,
-    m1: f32,
-    mut pos2: Mut<PhysicalTranslation>,
-    mut vel2: Mut<Velocity>,
-    m2: f32,
-    contact: Contact,
-    restitution: f32,
-) {
-    let n = contact.normal; // Vec2, unit, points 1 → 2
-    let n3 = n.extend(0.0); // Vec3
-
-    // ── 1. impulse (only if approaching) ────────────────────────────────
-    let rel_speed = (Vec2::new(vel1.x, vel1.y) - Vec2::new(vel2.x, vel2.y)).dot(n);
-
-    if rel_speed > 0.0 {
-        // approaching
-        let j = -(1.0 + restitution) * rel_speed / (1.0 / m1 + 1.0 / m2);
-        let impulse = n3 * j;
-        vel1.0 += impulse / m1;
-        vel2.0 -= impulse / m2;
+impl SolverContact {
+    /// Runs `f` with mutable access to both bodies. B is `None` for a static wall.
+    fn with_bodies(
+        &mut self,
+        q: &mut Query<CollisionBody>,
+        f: impl FnOnce(&mut Self, &mut CollisionBodyItem, Option<&mut CollisionBodyItem>),
+    ) {
+        let (a, b) = (self.constraint.a, self.constraint.b);
+        if self.constraint.b_is_static {
+            if let Ok(mut body_a) = q.get_mut(a) {
+                f(self, &mut body_a, None);
+            }
+        } else if let Ok([mut body_a, mut body_b]) = q.get_many_mut([a, b]) {
+            f(self, &mut body_a, Some(&mut body_b));
+        }
+    }
+
+    fn relative_velocity(&self, a: &CollisionBodyItem, b: &Option<&mut CollisionBodyItem>) -> Vec2 {
+        let v_b = b.as_ref().map_or(Vec2::ZERO, |b| b.velocity_at(self.r_b));
+        a.velocity_at(self.r_a) - v_b
     }
-    // If rel_speed ≤ 0 we skip the bounce, but we **continue** to separation
 
-    // ── 2. depenetration (always) ───────────────────────────────────────
-    if contact.penetration > 0.0 {
-        let total_m = m1 + m2;
-        let corr1 = contact.penetration * (m2 / total_m);
-        let corr2 = contact.penetration * (m1 / total_m);
+    fn inv_mass_along(
+        &self,
+        a: &CollisionBodyItem,
+        b: &Option<&mut CollisionBodyItem>,
+        dir: Vec2,
+    ) -> f32 {
+        let k_b = b
+            .as_ref()
+            .map_or(0.0, |b| b.effective_inv_mass(self.r_b, dir));
+        a.effective_inv_mass(self.r_a, dir) + k_b
+    }
 
-        pos1.0 -= n3 * corr1;
-        pos2.0 += n3 * corr2;
+    fn apply(&self, a: &mut CollisionBodyItem, b: Option<&mut CollisionBodyItem>, impulse: Vec2) {
+        a.apply_impulse(impulse, self.r_a);
+        if let Some(b) = b {
+            b.apply_impulse(-impulse, self.r_b);
+        }
+    }
+
+    /// One sequential impulse pass: the normal impulse, then Coulomb friction bounded by
+    /// μ times the normal impulse accumulated so far.
+    fn solve_velocity(&mut self, a: &mut CollisionBodyItem, mut b: Option<&mut CollisionBodyItem>) {
+        let n = self.constraint.contact.normal; // Vec2, unit, points B → A
+        let t = n.perp();
+
+        let k_n = self.inv_mass_along(a, &b, n);
+        if k_n <= 0.0 {
+            return;
+        }
+        let v_n = self.relative_velocity(a, &b).dot(n);
+        let old = self.impulse.normal;
+        self.impulse.normal = (old + (self.target_speed - v_n) / k_n).max(0.0);
+        self.apply(a, b.as_deref_mut(), n * (self.impulse.normal - old));
+
+        let k_t = self.inv_mass_along(a, &b, t);
+        let v_t = self.relative_velocity(a, &b).dot(t);
+        let max_friction = self.constraint.material.friction * self.impulse.normal;
+        let old = self.impulse.tangent;
+        self.impulse.tangent = (old - v_t / k_t).clamp(-max_friction, max_friction);
+        self.apply(a, b, t * (self.impulse.tangent - old));
+    }
+
+    /// Pushes the bodies apart by whatever penetration the previous passes left,
+    /// split by inverse mass so kinematic bodies and walls never move.
+    fn solve_position(&mut self, a: &mut CollisionBodyItem, b: Option<&mut CollisionBodyItem>) {
+        let n = self.constraint.contact.normal;
+        let moved_b = b
+            .as_ref()
+            .map_or(Vec2::ZERO, |b| b.pos.truncate() - self.start_b);
+        let separated = (a.pos.truncate() - self.start_a - moved_b).dot(n);
+        let remaining = self.constraint.contact.penetration - separated;
+
+        let inv_a = a.inv_mass();
+        let inv_b = b.as_ref().map_or(0.0, |b| b.inv_mass());
+        let total_inv = inv_a + inv_b;
+        if remaining <= 0.0 || total_inv <= 0.0 {
+            return;
+        }
+
+        let n3 = n.extend(0.0);
+        a.pos.0 += n3 * remaining * (inv_a / total_inv);
+        if let Some(b) = b {
+            b.pos.0 -= n3 * remaining * (inv_b / total_inv);
+        }
     }
 }
 
-fn circle_wall_contact_data(vel: Vec2, m: f32, contact: &Contact) -> ContactData {
-    let v_a_n = vel.dot(contact.normal);
-    let v_b_n = 0.0; // Because it's a wall
+impl CollisionBodyItem<'_, '_> {
+    fn isometry(&self) -> Isometry2d {
+        let rotation = self.rot.map_or(Rot2::IDENTITY, PhysicalRotation::rot2);
+        Isometry2d::new(self.pos.truncate(), rotation)
+    }
 
-    let impulse = if v_a_n < 0.0 {
-        -(1.0 + RESTITUTION) * v_a_n * m
-    } else {
-        0.0
-    };
+    fn is_dynamic(&self) -> bool {
+        RigidBody::is_dynamic(self.kind)
+    }
 
-    ContactData {
-        impulse,
-        v_a_n: -v_a_n,  // Flips the speed because the normal vector is point towards the circle.
-        v_b_n,
+    /// Whether the body may have moved this step. Sleeping and static bodies can't disturb anything.
+    fn is_awake(&self) -> bool {
+        !self.sleeping && self.kind.is_none_or(|kind| *kind != RigidBody::Static)
+    }
+
+    fn inv_mass(&self) -> f32 {
+        RigidBody::inv_mass(self.kind, self.mass.0)
+    }
+
+    /// Zero for bodies that can't rotate or aren't dynamic.
+    fn inv_inertia(&self) -> f32 {
+        if self.ang_vel.is_some() && self.is_dynamic() {
+            1.0 / self.collider.moment_of_inertia(self.mass.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn angular_speed(&self) -> f32 {
+        self.ang_vel.as_ref().map_or(0.0, |w| w.0)
+    }
+
+    /// Velocity of the point at offset `r` from the body's origin.
+    fn velocity_at(&self, r: Vec2) -> Vec2 {
+        self.vel.truncate() + self.angular_speed() * r.perp()
+    }
+
+    /// Inverse of the mass felt by an impulse along `dir` applied at offset `r`.
+    fn effective_inv_mass(&self, r: Vec2, dir: Vec2) -> f32 {
+        let r_cross_dir = r.perp_dot(dir);
+        self.inv_mass() + r_cross_dir * r_cross_dir * self.inv_inertia()
+    }
+
+    /// Applies `impulse` at offset `r`, changing both linear and angular velocity.
+    fn apply_impulse(&mut self, impulse: Vec2, r: Vec2) {
+        let (inv_mass, inv_inertia) = (self.inv_mass(), self.inv_inertia());
+        self.vel.0 += (impulse * inv_mass).extend(0.0);
+        if let Some(w) = self.ang_vel.as_mut() {
+            w.0 += r.perp_dot(impulse) * inv_inertia;
+        }
     }
 }
 
-fn circle_circle_contact_data(
-    v1: Vec2,
-    v2: Vec2,
-    m1: f32,
-    m2: f32,
+/// Contact data for A (velocity `v_a`) against B (velocity `v_b`), with the contact normal
+/// pointing from B to A. Speeds are reported along the A → B direction, taken before the impulse.
+fn contact_data(
+    v_a: Vec2,
+    v_b: Vec2,
     contact: &Contact,
+    applied: AppliedImpulse,
+    material: &PhysicsMaterial,
 ) -> ContactData {
-    let rel_vel = v2 - v1;
-    let closing = rel_vel.dot(contact.normal);
-
-    let impulse = if closing < 0.0 {
-        -(1.0 + RESTITUTION) * closing / (1.0 / m1 + 1.0 / m2)
-    } else {
-        0.0
-    };
-
-    let v_a_n = v1.dot(contact.normal);
-    let v_b_n = v2.dot(contact.normal);
+    let a_to_b = -contact.normal;
 
     ContactData {
-        impulse,
-        v_a_n,
-        v_b_n,
+        impulse: applied.normal,
+        friction_impulse: applied.tangent.abs(),
+        v_a_n: v_a.dot(a_to_b),
+        v_b_n: v_b.dot(a_to_b),
+        restitution: material.restitution,
+        friction: material.friction,
+        normal: contact.normal,
+        point: contact.point,
+        penetration: contact.penetration,
+        duration: 0.0,
     }
 }
@@ -0,0 +1,96 @@
+use crate::physics::*;
+use crate::prelude::*;
+
+/// Shortcuts for pushing a body around from `Commands`, adding up with whatever else
+/// was applied this step.
+pub trait PhysicsCommands {
+    /// Adds to the body's [`ExternalImpulse`].
+    fn apply_impulse(&mut self, impulse: Vec2) -> &mut Self;
+
+    /// Adds to the body's [`ExternalForce`]. If there isn't one yet, the force only lasts one step.
+    fn apply_force(&mut self, force: Vec2) -> &mut Self;
+
+    /// Queues a [`Teleport`].
+    fn teleport(&mut self, teleport: Teleport) -> &mut Self;
+}
+
+impl PhysicsCommands for EntityCommands<'_> {
+    fn apply_impulse(&mut self, impulse: Vec2) -> &mut Self {
+        self.entry::<ExternalImpulse>()
+            .or_default()
+            .and_modify(move |mut current| current.0 += impulse);
+        self
+    }
+
+    fn apply_force(&mut self, force: Vec2) -> &mut Self {
+        self.entry::<ExternalForce>()
+            .or_default()
+            .and_modify(move |mut current| current.force += force);
+        self
+    }
+
+    fn teleport(&mut self, teleport: Teleport) -> &mut Self {
+        self.queue(teleport)
+    }
+}
+
+/// Moves a body straight to `position`, for respawns and portals.
+///
+/// Unlike writing [`PhysicalTranslation`] directly, the previous translation and the `Transform`
+/// are moved too, so the sprite doesn't slide across the screen on the next frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Teleport {
+    pub position: Vec2,
+    pub stop: bool, // zeroes the linear and angular velocity on arrival
+}
+
+impl Teleport {
+    /// Keeps the body's velocity, like going through a portal.
+    pub fn to(position: Vec2) -> Self {
+        Self {
+            position,
+            stop: false,
+        }
+    }
+
+    /// Leaves the body at rest, like a respawn.
+    pub fn stopped(position: Vec2) -> Self {
+        Self {
+            position,
+            stop: true,
+        }
+    }
+}
+
+impl EntityCommand for Teleport {
+    fn apply(self, mut entity: EntityWorldMut) {
+        // The depth is only used for drawing order, so the body keeps its own
+        if let Some(mut translation) = entity.get_mut::<PhysicalTranslation>() {
+            translation.x = self.position.x;
+            translation.y = self.position.y;
+        }
+        if let Some(mut previous) = entity.get_mut::<PreviousPhysicalTranslation>() {
+            previous.x = self.position.x;
+            previous.y = self.position.y;
+        }
+        if let Some(mut transform) = entity.get_mut::<Transform>() {
+            transform.translation.x = self.position.x;
+            transform.translation.y = self.position.y;
+        }
+
+        if self.stop {
+            if let Some(mut velocity) = entity.get_mut::<Velocity>() {
+                velocity.0 = Vec3::ZERO;
+            }
+            if let Some(mut angular_velocity) = entity.get_mut::<AngularVelocity>() {
+                angular_velocity.0 = 0.0;
+            }
+        }
+
+        // It may land right on top of something
+        if let Some(mut timer) = entity.get_mut::<SleepTimer>() {
+            timer.0 = 0;
+        }
+        entity.remove::<Sleeping>();
+    }
+}
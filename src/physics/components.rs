@@ -1,8 +1,39 @@
 use crate::prelude::*;
+use bevy::ecs::lifecycle::HookContext;
+use bevy::ecs::world::DeferredWorld;
 
 #[derive(Component, Default)]
 pub struct Movable;
 
+/// How a body takes part in the simulation. Movers without one are dynamic.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Default, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub enum RigidBody {
+    /// Driven by input, drag and collision impulses.
+    #[default]
+    Dynamic,
+    /// Moves only by its own `Velocity`. Pushes dynamic bodies but ignores impulses.
+    Kinematic,
+    /// Never moves.
+    Static,
+}
+
+impl RigidBody {
+    /// Whether a body of this kind is moved by impulses. Bodies without a `RigidBody` are.
+    pub fn is_dynamic(kind: Option<&RigidBody>) -> bool {
+        kind.is_none_or(|kind| *kind == RigidBody::Dynamic)
+    }
+
+    /// One over `mass`, or zero for kinematic and static bodies, which impulses can't move.
+    pub fn inv_mass(kind: Option<&RigidBody>, mass: f32) -> f32 {
+        if Self::is_dynamic(kind) {
+            1.0 / mass
+        } else {
+            0.0
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Mass(pub f32);
 
@@ -12,6 +43,15 @@ impl Default for Mass {
     }
 }
 
+/// Per-entity movement tuning. Bodies without one use the values in `PhysicsConfig`.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct MovementStats {
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub drag: f32,
+}
+
 /// A vector representing the player's velocity in the physics simulation.
 #[derive(
     Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
@@ -26,22 +66,179 @@ pub struct Velocity(pub Vec3);
 #[reflect(Component)]
 pub struct Acceleration(pub Vec3);
 
+/// Force in newtons pushing a dynamic body, divided by its `Mass` in `advance_physics`.
+///
+/// A persistent force keeps acting every step (wind, thrusters), otherwise it's cleared
+/// once applied.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct ExternalForce {
+    pub force: Vec2,
+    pub persistent: bool,
+}
+
+impl ExternalForce {
+    pub fn new(force: Vec2) -> Self {
+        Self {
+            force,
+            persistent: false,
+        }
+    }
+
+    pub fn persistent(force: Vec2) -> Self {
+        Self {
+            force,
+            persistent: true,
+        }
+    }
+}
+
+/// Instant change of momentum in N·s (knockback, explosions, recoil), applied on the next
+/// fixed step and then cleared.
+#[derive(
+    Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
+)]
+#[reflect(Component)]
+pub struct ExternalImpulse(pub Vec2);
+
+/// Changes how dynamic bodies move while their centre is inside this entity's [`Collider`],
+/// placed by its `Transform`. Overlapping effectors add up their forces.
+///
+/// The region doesn't collide with anything unless it is also a `Wall` or a [`Sensor`],
+/// and only affects bodies whose [`CollisionLayers`] interact with its own.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct AreaEffector {
+    pub radial_force: f32, // N towards the region's centre, negative pushes away from it
+    pub force: Vec2,       // N, the same everywhere in the region
+    pub drag: Option<f32>, // replaces the body's drag; the highest wins where several overlap
+}
+
+impl AreaEffector {
+    /// Gravity wells (positive) and repulsors (negative).
+    pub fn radial(radial_force: f32) -> Self {
+        Self {
+            radial_force,
+            ..default()
+        }
+    }
+
+    /// Wind zones and conveyor belts.
+    pub fn directional(force: Vec2) -> Self {
+        Self {
+            force,
+            ..default()
+        }
+    }
+
+    /// Ice patches (low) and mud pits (high).
+    pub fn drag(drag: f32) -> Self {
+        Self {
+            drag: Some(drag),
+            ..default()
+        }
+    }
+
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = Some(drag);
+        self
+    }
+}
+
+/// Marks a dynamic body that has been still long enough to be skipped by the simulation.
+///
+/// Sleeping bodies aren't integrated and aren't tested against walls or other sleeping bodies.
+/// They wake up on input, on an [`ExternalImpulse`] or [`ExternalForce`], when something pushes
+/// them, or when any body of their island (the bodies they touch or are jointed to) does.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct Sleeping;
+
+/// Fixed steps in a row a body has spent slower than [`PhysicsConfig::sleep_speed`].
+/// Sleeping is opt-in: bodies without one, like the player, never fall asleep.
+#[derive(
+    Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
+)]
+#[reflect(Component)]
+pub struct SleepTimer(pub u32);
+
 /// The actual position of the player in the physics simulation.
 /// This is separate from the `Transform`, which is merely a visual representation.
 ///
-/// If you want to make sure that this component is always initialized
-/// with the same value as the `Transform`'s translation, you can
-/// use a [component lifecycle hook](https://docs.rs/bevy/0.14.0/bevy/ecs/component/struct.ComponentHooks.html)
+/// When it's first added to an entity that has a `Transform`, it starts at the `Transform`'s
+/// translation, so bodies spawn where they are drawn. Inserting it again keeps the given value,
+/// but [`Teleport`] is the way to move a body without it sliding across the screen.
 #[derive(
     Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
 )]
+#[component(on_add)]
 #[reflect(Component)]
 pub struct PhysicalTranslation(pub Vec3);
 
+impl PhysicalTranslation {
+    fn on_add(mut world: DeferredWorld, context: HookContext) {
+        if let Some(translation) = transform_translation(&world, context.entity)
+            && let Some(mut physical) = world.get_mut::<Self>(context.entity)
+        {
+            physical.0 = translation;
+        }
+    }
+}
+
 /// The value [`PhysicalTranslation`] had in the last fixed timestep.
 /// Used for interpolation in the `interpolate_rendered_transform` system.
+///
+/// Seeded from the `Transform` when added, like [`PhysicalTranslation`], so a new body
+/// isn't interpolated in from the origin.
 #[derive(
     Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
 )]
+#[component(on_add)]
 #[reflect(Component)]
 pub struct PreviousPhysicalTranslation(pub Vec3);
+
+impl PreviousPhysicalTranslation {
+    fn on_add(mut world: DeferredWorld, context: HookContext) {
+        if let Some(translation) = transform_translation(&world, context.entity)
+            && let Some(mut previous) = world.get_mut::<Self>(context.entity)
+        {
+            previous.0 = translation;
+        }
+    }
+}
+
+/// The translation of the entity's `Transform`, if it has one.
+fn transform_translation(world: &DeferredWorld, entity: Entity) -> Option<Vec3> {
+    world.get::<Transform>(entity).map(|tf| tf.translation)
+}
+
+/// The actual rotation around the z axis, in radians, of a body in the physics simulation.
+/// Like [`PhysicalTranslation`], it's only copied to the `Transform` for rendering.
+///
+/// Bodies without it never rotate, as if their moment of inertia was infinite.
+#[derive(
+    Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
+)]
+#[reflect(Component)]
+pub struct PhysicalRotation(pub f32);
+
+impl PhysicalRotation {
+    pub fn rot2(&self) -> Rot2 {
+        Rot2::radians(self.0)
+    }
+}
+
+/// The value [`PhysicalRotation`] had in the last fixed timestep.
+/// Used for interpolation in the `interpolate_rendered_transform` system.
+#[derive(
+    Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
+)]
+#[reflect(Component)]
+pub struct PreviousPhysicalRotation(pub f32);
+
+/// Counter-clockwise rotation speed, in rad/s.
+#[derive(
+    Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut, Reflect, InspectorOptions,
+)]
+#[reflect(Component)]
+pub struct AngularVelocity(pub f32);
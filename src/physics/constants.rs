@@ -1,3 +1,8 @@
 pub const DEFAULT_MAX_SPEED: f32 = 500.0;
 pub const DEFAULT_DRAG: f32 = 250.0;
 pub const DEFAULT_ACCELERATION: f32 = 1000.0;
+pub const DEFAULT_ANGULAR_DRAG: f32 = 2.0 * std::f32::consts::PI;
+pub const DEFAULT_SUBSTEPS: u32 = 1;
+pub const DEFAULT_SLEEP_SPEED: f32 = 5.0;
+pub const DEFAULT_SLEEP_ANGULAR_SPEED: f32 = 0.1;
+pub const DEFAULT_SLEEP_STEPS: u32 = 64;
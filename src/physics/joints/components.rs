@@ -0,0 +1,70 @@
+use crate::prelude::*;
+
+// Joints live on their own entities and link bodies by their `PhysicalTranslation`.
+// A joint whose bodies are gone is skipped; despawn it along with them.
+
+/// Keeps two bodies exactly `length` apart, like a rigid rod between their centres.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct DistanceJoint {
+    pub a: Entity,
+    pub b: Entity,
+    pub length: f32,
+}
+
+impl DistanceJoint {
+    pub fn new(a: Entity, b: Entity, length: f32) -> Self {
+        Self { a, b, length }
+    }
+}
+
+/// Pulls two bodies towards `rest_length` apart with a damped spring.
+///
+/// `stiffness` is the force per unit of stretch, `damping` the force per unit of
+/// relative speed along the spring. Very stiff springs need more sub-steps to stay stable.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct SpringJoint {
+    pub a: Entity,
+    pub b: Entity,
+    pub rest_length: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl SpringJoint {
+    pub fn new(a: Entity, b: Entity, rest_length: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            a,
+            b,
+            rest_length,
+            stiffness,
+            damping,
+        }
+    }
+}
+
+/// Keeps a body exactly `length` from a fixed world point.
+/// A length of zero nails it to the point; anything longer swings it around like a pendulum.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Component)]
+pub struct PinJoint {
+    pub body: Entity,
+    pub anchor: Vec2,
+    pub length: f32,
+}
+
+impl PinJoint {
+    pub fn new(body: Entity, anchor: Vec2) -> Self {
+        Self {
+            body,
+            anchor,
+            length: 0.0,
+        }
+    }
+
+    pub fn with_length(mut self, length: f32) -> Self {
+        self.length = length;
+        self
+    }
+}
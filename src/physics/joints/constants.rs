@@ -0,0 +1,3 @@
+/// Solver passes over the rigid joints of each sub-step.
+/// Long chains need more passes to stop stretching.
+pub const DEFAULT_JOINT_ITERATIONS: u32 = 8;
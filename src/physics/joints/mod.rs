@@ -0,0 +1,11 @@
+pub mod components;
+pub mod constants;
+pub mod plugin;
+pub mod resources;
+pub mod systems;
+
+pub use components::*;
+pub use constants::*;
+pub use plugin::*;
+pub use resources::*;
+pub use systems::*;
@@ -0,0 +1,17 @@
+use crate::physics::*;
+use crate::prelude::*;
+
+pub struct JointPlugin;
+
+impl Plugin for JointPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<JointConfig>().add_systems(
+            PhysicsSubstep,
+            // Bodies have moved, but their collisions haven't been resolved yet,
+            // so contacts get the final say over where a jointed body ends up.
+            solve_joints
+                .after(advance_physics)
+                .in_set(PhysicsSet::Integrate),
+        );
+    }
+}
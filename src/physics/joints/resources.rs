@@ -0,0 +1,17 @@
+use crate::physics::*;
+use crate::prelude::*;
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Resource)]
+pub struct JointConfig {
+    /// Passes the solver makes over the distance and pin joints. More passes keep chains tighter.
+    pub iterations: u32,
+}
+
+impl Default for JointConfig {
+    fn default() -> Self {
+        JointConfig {
+            iterations: DEFAULT_JOINT_ITERATIONS,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::physics::*;
+use crate::prelude::*;
+use bevy::ecs::query::QueryData;
+
+/// What the joint solver reads or writes on a jointed body.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct JointBody {
+    pos: &'static mut PhysicalTranslation,
+    vel: &'static mut Velocity,
+    mass: Option<&'static Mass>,
+    kind: Option<&'static RigidBody>,
+    sleeping: Has<Sleeping>,
+}
+
+/// Applies the spring forces, then pulls distance and pin joints back to their length.
+///
+/// Rigid joints are solved on positions, with the relative velocity along the joint removed,
+/// so bodies swing around each other instead of drifting apart.
+/// Joints between sleeping bodies are left alone.
+pub fn solve_joints(
+    clock: PhysicsClock,
+    physics_cfg: Res<PhysicsConfig>,
+    cfg: Res<JointConfig>,
+    springs: Query<&SpringJoint>,
+    distances: Query<&DistanceJoint>,
+    pins: Query<&PinJoint>,
+    mut bodies: Query<JointBody>,
+) {
+    let dt = physics_cfg.substep_secs(clock.delta_secs());
+
+    for spring in &springs {
+        let Ok([mut a, mut b]) = bodies.get_many_mut([spring.a, spring.b]) else {
+            continue;
+        };
+        if a.sleeping && b.sleeping {
+            continue;
+        }
+        let Ok((dir, len)) = Dir2::new_and_length(b.pos.truncate() - a.pos.truncate()) else {
+            continue;
+        };
+        let (w_a, w_b) = (a.inv_mass(), b.inv_mass());
+        let rel_speed = (b.vel.truncate() - a.vel.truncate()).dot(*dir);
+        // Positive pulls the bodies together
+        let force = spring.stiffness * (len - spring.rest_length) + spring.damping * rel_speed;
+        a.vel.0 += (dir * force * w_a * dt).extend(0.0);
+        b.vel.0 -= (dir * force * w_b * dt).extend(0.0);
+    }
+
+    for _ in 0..cfg.iterations.max(1) {
+        for joint in &distances {
+            let Ok([mut a, mut b]) = bodies.get_many_mut([joint.a, joint.b]) else {
+                continue;
+            };
+            if a.sleeping && b.sleeping {
+                continue;
+            }
+            let anchor = b.pos.truncate();
+            let (w_a, w_b) = (a.inv_mass(), b.inv_mass());
+            if let Some((shift, dv)) =
+                distance_correction(&a, anchor, b.vel.truncate(), w_a + w_b, joint.length)
+            {
+                a.pos.0 += (shift * w_a).extend(0.0);
+                a.vel.0 += (dv * w_a).extend(0.0);
+                b.pos.0 -= (shift * w_b).extend(0.0);
+                b.vel.0 -= (dv * w_b).extend(0.0);
+            }
+        }
+
+        for pin in &pins {
+            let Ok(mut body) = bodies.get_mut(pin.body) else {
+                continue;
+            };
+            if body.sleeping {
+                continue;
+            }
+            let w = body.inv_mass();
+            if let Some((shift, dv)) =
+                distance_correction(&body, pin.anchor, Vec2::ZERO, w, pin.length)
+            {
+                body.pos.0 += (shift * w).extend(0.0);
+                body.vel.0 += (dv * w).extend(0.0);
+            }
+        }
+    }
+}
+
+//--------------------------------------------------
+// Helper functions
+//--------------------------------------------------
+
+/// Position shift and velocity change, per unit of inverse mass, that bring `body` back to
+/// `length` from `anchor` and cancel its speed relative to the anchor along the joint.
+/// The anchor side takes the opposite of both. `None` when neither side can move.
+fn distance_correction(
+    body: &JointBodyItem,
+    anchor: Vec2,
+    anchor_vel: Vec2,
+    inv_mass_sum: f32,
+    length: f32,
+) -> Option<(Vec2, Vec2)> {
+    if inv_mass_sum <= 0.0 {
+        return None;
+    }
+    let (dir, dist) = Dir2::new_and_length(anchor - body.pos.truncate()).ok()?;
+    let rel_speed = (anchor_vel - body.vel.truncate()).dot(*dir);
+    let shift = dir * (dist - length) / inv_mass_sum;
+    let dv = dir * rel_speed / inv_mass_sum;
+    Some((shift, dv))
+}
+
+impl JointBodyItem<'_, '_> {
+    /// Zero for kinematic and static bodies, which joints can't move.
+    fn inv_mass(&self) -> f32 {
+        RigidBody::inv_mass(self.kind, self.mass.map_or(1.0, |mass| mass.0))
+    }
+}
@@ -1,4 +1,5 @@
 pub mod collision;
+pub mod joints;
 
 pub mod bundles;
 pub mod plugin;
@@ -6,8 +7,10 @@ pub mod resources;
 pub mod systems;
 pub mod components;
 pub mod constants;
+pub mod commands;
 
 pub use collision::*;
+pub use joints::*;
 
 pub use bundles::*;
 pub use plugin::*;
@@ -15,3 +18,4 @@ pub use resources::*;
 pub use systems::*;
 pub use components::*;
 pub use constants::*;
+pub use commands::*;
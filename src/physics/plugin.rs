@@ -1,15 +1,92 @@
 use crate::physics::*;
 use crate::prelude::*;
+use bevy::ecs::schedule::ScheduleLabel;
+
+/// One pass of the simulation, run [`PhysicsConfig::substeps`] times per fixed timestep.
+/// Each pass moves the bodies and resolves their collisions over a fraction of the timestep.
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct PhysicsSubstep;
+
+/// The stages of a fixed step, chained in this order in `FixedUpdate`.
+/// Gameplay systems can be placed in or around them, and skip with the simulation
+/// whenever [`PhysicsTime`] is paused.
+///
+/// `Integrate`, `Detect` and `Solve` are chained in the [`PhysicsSubstep`] schedule too,
+/// where the work of every sub-step happens. In `FixedUpdate`, the sub-steps all run in
+/// `Integrate`, so systems added to `Detect` or `Solve` there run once, after all of them.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum PhysicsSet {
+    /// Before anything moves: forces, impulses and teleports land here.
+    PrePhysics,
+    /// Bodies and joints move.
+    Integrate,
+    /// Contacts are found.
+    Detect,
+    /// Contacts are resolved.
+    Solve,
+    /// `CollisionEvent`s are written and `CollisionStart` / `CollisionEnd` triggered.
+    EmitEvents,
+    /// After the step: reacting to collisions, sleeping, clearing inputs.
+    PostPhysics,
+}
 
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(CollisionPlugin)
+        app.add_plugins((CollisionPlugin, JointPlugin))
+            // Ticks skipped by a paused `PhysicsTime` leave every body where it is.
+            .configure_sets(
+                FixedUpdate,
+                (
+                    PhysicsSet::PrePhysics,
+                    PhysicsSet::Integrate,
+                    PhysicsSet::Detect,
+                    PhysicsSet::Solve,
+                    PhysicsSet::EmitEvents,
+                    PhysicsSet::PostPhysics,
+                )
+                    .chain()
+                    .run_if(physics_running),
+            )
+            .configure_sets(
+                PhysicsSubstep,
+                (PhysicsSet::Integrate, PhysicsSet::Detect, PhysicsSet::Solve).chain(),
+            )
             // Constants used for physics systems.
             .insert_resource(PhysicsConfig::default())
-            // Advance the physics simulation using a fixed timestep.
-            .add_systems(FixedUpdate, advance_physics)
+            .init_resource::<PhysicsTime>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_physics_time,
+                    discard_skipped_inputs.run_if(not(physics_running)),
+                )
+                    .chain()
+                    .before(PhysicsSet::PrePhysics),
+            )
+            .add_systems(
+                FixedUpdate,
+                (wake_disturbed_bodies, store_previous_physical_state)
+                    .chain()
+                    .in_set(PhysicsSet::PrePhysics),
+            )
+            // Advance the physics simulation using a fixed timestep, split into sub-steps.
+            .add_systems(
+                FixedUpdate,
+                run_physics_substeps.in_set(PhysicsSet::Integrate),
+            )
+            // Bodies fall asleep once the step's contacts are settled.
+            .add_systems(
+                FixedUpdate,
+                (update_sleeping_bodies, consume_step_inputs)
+                    .chain()
+                    .in_set(PhysicsSet::PostPhysics),
+            )
+            .add_systems(
+                PhysicsSubstep,
+                advance_physics.in_set(PhysicsSet::Integrate),
+            )
             .add_systems(
                 // The `RunFixedMainLoop` schedule allows us to schedule systems to run before and after the fixed timestep loop.
                 RunFixedMainLoop,
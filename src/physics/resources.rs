@@ -1,12 +1,48 @@
 use crate::prelude::*;
 use crate::physics::*;
 
+/// How `drag` slows a body down. The unit of `drag` depends on the mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, InspectorOptions)]
+pub enum DragMode {
+    /// Speed drops by `drag` px/s every second, then stops dead.
+    #[default]
+    ConstantDeceleration,
+    /// Deceleration proportional to the speed, `drag` in 1/s. Never quite stops.
+    Linear,
+    /// Deceleration proportional to the squared speed, `drag` in 1/px. Brakes hard at high speed.
+    Quadratic,
+}
+
+impl DragMode {
+    /// `velocity` after `dt` seconds of drag, along its own direction.
+    pub fn apply(self, velocity: Vec2, drag: f32, dt: f32) -> Vec2 {
+        let speed = velocity.length();
+        if speed < f32::EPSILON {
+            return Vec2::ZERO;
+        }
+
+        // Exact solutions over the step, so a large `drag * dt` can't reverse the velocity
+        let new_speed = match self {
+            DragMode::ConstantDeceleration => (speed - drag * dt).max(0.0),
+            DragMode::Linear => speed * (-drag * dt).exp(),
+            DragMode::Quadratic => speed / (1.0 + drag * speed * dt),
+        };
+        velocity * (new_speed / speed)
+    }
+}
+
 #[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
 #[reflect(Resource)]
 pub struct PhysicsConfig {
     pub max_speed: f32,
     pub acceleration: f32,
     pub drag: f32,
+    pub drag_mode: DragMode,
+    pub angular_drag: f32,        // rad/s²
+    pub substeps: u32,            // physics passes per fixed timestep
+    pub sleep_speed: f32,         // px/s under which a body counts as still
+    pub sleep_angular_speed: f32, // rad/s under which a body counts as still
+    pub sleep_steps: u32,         // still fixed steps before an island falls asleep, 0 never sleeps
 }
 
 impl Default for PhysicsConfig {
@@ -15,12 +51,157 @@ impl Default for PhysicsConfig {
             max_speed: DEFAULT_MAX_SPEED,
             acceleration: DEFAULT_ACCELERATION,
             drag: DEFAULT_DRAG,
+            drag_mode: DragMode::default(),
+            angular_drag: DEFAULT_ANGULAR_DRAG,
+            substeps: DEFAULT_SUBSTEPS,
+            sleep_speed: DEFAULT_SLEEP_SPEED,
+            sleep_angular_speed: DEFAULT_SLEEP_ANGULAR_SPEED,
+            sleep_steps: DEFAULT_SLEEP_STEPS,
         }
     }
 }
 
 impl PhysicsConfig {
-    pub fn max_speed_sq(&self) -> f32 {
-        self.max_speed * self.max_speed
+    /// Length of one sub-step, given the fixed timestep.
+    pub fn substep_secs(&self, fixed_dt: f32) -> f32 {
+        fixed_dt / self.substeps.max(1) as f32
+    }
+
+    /// The movement values used by bodies without their own `MovementStats`.
+    pub fn movement_stats(&self) -> MovementStats {
+        MovementStats {
+            max_speed: self.max_speed,
+            acceleration: self.acceleration,
+            drag: self.drag,
+        }
+    }
+
+    /// Whether a body moving this slowly counts as still for sleeping.
+    pub fn is_resting(&self, velocity: Vec3, angular_velocity: f32) -> bool {
+        velocity.length_squared() < self.sleep_speed * self.sleep_speed
+            && angular_velocity.abs() < self.sleep_angular_speed
+    }
+}
+
+/// Clock of the simulation, on top of Bevy's `Time<Fixed>`.
+///
+/// The fixed timestep keeps ticking at the same rate whatever is set here: a paused simulation
+/// skips its ticks, and the time scale shortens or stretches how far each tick advances it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect, InspectorOptions)]
+#[reflect(Resource)]
+pub struct PhysicsTime {
+    pub paused: bool,
+    pub time_scale: f32, // 1 is real time, 0.25 is slow motion
+    pending_steps: u32,  // ticks to run while paused
+    running: bool,       // whether the current fixed tick advances the simulation
+}
+
+impl Default for PhysicsTime {
+    fn default() -> Self {
+        PhysicsTime {
+            paused: false,
+            time_scale: 1.0,
+            pending_steps: 0,
+            running: true,
+        }
+    }
+}
+
+impl PhysicsTime {
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.pending_steps = 0;
+    }
+
+    pub fn toggle(&mut self) {
+        if self.paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Runs one more fixed tick while paused. Does nothing while running.
+    pub fn step(&mut self) {
+        if self.paused {
+            self.pending_steps += 1;
+        }
+    }
+
+    /// Whether the current fixed tick advances the simulation.
+    pub fn is_running(&self) -> bool {
+        self.running
     }
-}
\ No newline at end of file
+
+    /// Simulated seconds covered by a fixed tick of `fixed_dt` real seconds.
+    pub fn scaled_secs(&self, fixed_dt: f32) -> f32 {
+        fixed_dt * self.time_scale.max(0.0)
+    }
+
+    /// Decides whether the fixed tick that's starting runs, using up a pending step if paused.
+    pub(crate) fn tick(&mut self) {
+        self.running = !self.paused || self.pending_steps > 0;
+        if self.paused && self.running {
+            self.pending_steps -= 1;
+        }
+    }
+}
+
+/// Runs one fixed tick of a paused simulation. See [`PhysicsTime::step`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StepPhysics;
+
+impl Command for StepPhysics {
+    fn apply(self, world: &mut World) {
+        world.resource_mut::<PhysicsTime>().step();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paused_time_only_runs_the_requested_steps() {
+        let mut time = PhysicsTime::default();
+        time.tick();
+        assert!(time.is_running());
+
+        time.pause();
+        time.step();
+        time.step();
+        let ran = (0..5)
+            .filter(|_| {
+                time.tick();
+                time.is_running()
+            })
+            .count();
+        assert_eq!(ran, 2);
+
+        time.time_scale = 0.5;
+        assert_eq!(time.scaled_secs(0.1), 0.05);
+        time.time_scale = -1.0;
+        assert_eq!(time.scaled_secs(0.1), 0.0);
+    }
+
+    #[test]
+    fn drag_modes_slow_down_without_reversing() {
+        let velocity = Vec2::new(300.0, 400.0);
+        let constant = DragMode::ConstantDeceleration.apply(velocity, 100.0, 1.0);
+        assert!(constant.abs_diff_eq(velocity * 0.8, 1e-3));
+        let stopped = DragMode::ConstantDeceleration.apply(velocity, 1e6, 1.0);
+        assert_eq!(stopped, Vec2::ZERO);
+
+        let linear = DragMode::Linear.apply(velocity, 2.0, 0.5);
+        assert!((linear.length() - 500.0 * (-1.0f32).exp()).abs() < 1e-3);
+        assert!(DragMode::Linear.apply(velocity, 1e3, 1.0).dot(velocity) >= 0.0);
+
+        let quadratic = DragMode::Quadratic.apply(velocity, 0.01, 0.1);
+        assert!((quadratic.length() - 500.0 / 1.5).abs() < 1e-3);
+        assert!(quadratic.angle_to(velocity).abs() < 1e-6);
+    }
+}
@@ -1,84 +1,406 @@
 use core::f32;
+use std::collections::{HashMap, HashSet};
 
 use crate::physics::*;
 use crate::prelude::*;
+use bevy::ecs::system::SystemParam;
 
-/// Advance the physics simulation by one fixed timestep. This may run zero or multiple times per frame.
+/// Decides whether this fixed tick advances the simulation. See [`PhysicsTime`].
+pub fn tick_physics_time(mut time: ResMut<PhysicsTime>) {
+    time.tick();
+}
+
+/// The fixed timestep as the simulation sees it, scaled by [`PhysicsTime`].
+#[derive(SystemParam)]
+pub struct PhysicsClock<'w> {
+    fixed_time: Res<'w, Time<Fixed>>,
+    physics_time: Res<'w, PhysicsTime>,
+}
+
+impl PhysicsClock<'_> {
+    /// Simulated seconds covered by the current fixed tick.
+    pub fn delta_secs(&self) -> f32 {
+        self.physics_time.scaled_secs(self.fixed_time.delta_secs())
+    }
+}
+
+/// Run condition for the systems that advance the simulation.
+pub fn physics_running(time: Res<PhysicsTime>) -> bool {
+    time.is_running()
+}
+
+/// Runs the [`PhysicsSubstep`] schedule [`PhysicsConfig::substeps`] times.
+pub fn run_physics_substeps(world: &mut World) {
+    let substeps = world.resource::<PhysicsConfig>().substeps.max(1);
+    for _ in 0..substeps {
+        world.run_schedule(PhysicsSubstep);
+    }
+}
+
+/// Remembers where every body is before the sub-steps of this fixed timestep move it.
+#[allow(clippy::type_complexity)]
+pub fn store_previous_physical_state(
+    mut query: Query<
+        (
+            &PhysicalTranslation,
+            &mut PreviousPhysicalTranslation,
+            Option<(&PhysicalRotation, &mut PreviousPhysicalRotation)>,
+        ),
+        With<Movable>,
+    >,
+) {
+    for (current, mut previous, rotation) in query.iter_mut() {
+        previous.0 = current.0;
+        if let Some((current_rotation, mut previous_rotation)) = rotation {
+            previous_rotation.0 = current_rotation.0;
+        }
+    }
+}
+
+/// An [`AreaEffector`] with its region placed in the world.
+type EffectorRegion<'a> = (
+    Entity,
+    &'a AreaEffector,
+    &'a Collider,
+    Isometry2d,
+    CollisionLayers,
+);
+
+/// Advance the physics simulation by one sub-step. This may run zero or multiple times per frame.
 ///
-/// Note that since this runs in `FixedUpdate`, `Res<Time>` would be `Res<Time<Fixed>>` automatically.
-/// We are being explicit here for clarity.
+/// The sub-step covers a share of [`PhysicsClock::delta_secs`], the fixed timestep
+/// scaled by [`PhysicsTime::time_scale`].
+#[allow(clippy::type_complexity)]
 pub fn advance_physics(
-    fixed_time: Res<Time<Fixed>>,
+    clock: PhysicsClock,
     cfg: Res<PhysicsConfig>,
     mut query: Query<
         (
+            Entity,
             &mut PhysicalTranslation,
-            &mut PreviousPhysicalTranslation,
-            &mut AccumulatedInput,
+            &AccumulatedInput,
             &mut Velocity,
             &mut Acceleration,
+            Option<(&mut PhysicalRotation, &mut AngularVelocity)>,
+            Option<&RigidBody>,
+            Option<&MovementStats>,
+            Option<&Mass>,
+            Option<&ExternalForce>,
+            Option<&mut ExternalImpulse>,
+            Option<&CollisionLayers>,
         ),
-        With<Movable>,
+        (With<Movable>, Without<Sleeping>),
+    >,
+    effectors: Query<(
+        Entity,
+        &AreaEffector,
+        &Collider,
+        &Transform,
+        Option<&CollisionLayers>,
+    )>,
+    // Effectors on movers are placed through `query` instead, which already borrows their state
+    placed_effectors: Query<
+        (Option<&PhysicalTranslation>, Option<&PhysicalRotation>),
+        (With<AreaEffector>, Without<Movable>),
     >,
 ) {
-    let dt = fixed_time.delta_secs();
-    let max_speed_sq = cfg.max_speed_sq();
+    let dt = cfg.substep_secs(clock.delta_secs());
+    let default_stats = cfg.movement_stats();
+    let regions: Vec<EffectorRegion> = effectors
+        .iter()
+        .map(|(entity, effector, collider, transform, layers)| {
+            // Placed by the simulation rather than the interpolated `Transform` when it can be
+            let mut iso = transform_isometry(transform);
+            if let Ok(mover) = query.get(entity) {
+                iso.translation = mover.1.truncate();
+                if let Some((rot, _)) = mover.5 {
+                    iso.rotation = rot.rot2();
+                }
+            } else if let Ok((pos, rot)) = placed_effectors.get(entity) {
+                if let Some(pos) = pos {
+                    iso.translation = pos.truncate();
+                }
+                if let Some(rot) = rot {
+                    iso.rotation = rot.rot2();
+                }
+            }
+
+            let layers = layers.copied().unwrap_or_default();
+            (entity, effector, collider, iso, layers)
+        })
+        .collect();
 
     for (
+        entity,
         mut current_physical_translation,
-        mut previous_physical_translation,
-        mut input,
+        input,
         mut velocity,
         mut acceleration,
+        rotation,
+        kind,
+        stats,
+        mass,
+        force,
+        impulse,
+        layers,
     ) in query.iter_mut()
     {
-        // Need to normalize and scale because otherwise diagonal movement would be faster than horizontal or vertical
-        // movement.
-        // This effectively averages the accumulated input.
-        acceleration.0 = input.extend(0.0).normalize_or_zero() * cfg.acceleration;
+        let kind = kind.copied().unwrap_or_default();
+        let stats = stats.unwrap_or(&default_stats);
+
+        // Kinematic bodies keep whatever velocity they were given, static ones don't move at all.
+        if kind == RigidBody::Dynamic {
+            // Need to normalize and scale because otherwise diagonal movement would be faster than horizontal or vertical
+            // movement.
+            // This effectively averages the accumulated input.
+            acceleration.0 = input.extend(0.0).normalize_or_zero() * stats.acceleration;
+
+            // Drag brakes everything except the part of the velocity going where the input points,
+            // so letting go, turning and strafing all decelerate the same way in every direction.
+            let input_dir = acceleration.truncate().normalize_or_zero();
+            let kept = velocity.truncate().dot(input_dir).max(0.0) * input_dir;
+            let (area_force, area_drag) = area_effects(
+                &regions,
+                entity,
+                current_physical_translation.truncate(),
+                layers.copied().unwrap_or_default(),
+            );
+            let drag = area_drag.unwrap_or(stats.drag);
+            let braked = cfg.drag_mode.apply(velocity.truncate() - kept, drag, dt);
+            velocity.0 = (kept + braked).extend(velocity.z);
+
+            // Input can't push a body past its max speed, but doesn't hold back one that
+            // something else launched faster. Drag brings that one back down.
+            let max_speed = stats.max_speed.max(velocity.length());
+            velocity.0 += acceleration.0 * dt;
+            if velocity.0.length_squared() > max_speed * max_speed {
+                velocity.0 = velocity.normalize_or_zero() * max_speed;
+            }
 
-        let drag_component = cfg.drag * dt;
+            // External pushes go straight to the velocity after the cap, so knockbacks,
+            // explosions and wind can launch a body faster than it walks.
+            let inv_mass = 1.0 / mass.map_or(1.0, |m| m.0);
+            velocity.0 += (area_force * inv_mass * dt).extend(0.0);
+            if let Some(force) = force {
+                velocity.0 += (force.force * inv_mass * dt).extend(0.0);
+            }
+            if let Some(impulse) = &impulse {
+                velocity.0 += (impulse.0 * inv_mass).extend(0.0);
+            }
+        }
+
+        if kind != RigidBody::Static {
+            current_physical_translation.0 += velocity.0 * dt;
+        }
+
+        if let Some((mut rotation, mut angular_velocity)) = rotation {
+            if kind == RigidBody::Dynamic {
+                apply_drag_component(&mut angular_velocity.0, cfg.angular_drag * dt);
+            }
+
+            if kind != RigidBody::Static {
+                rotation.0 += angular_velocity.0 * dt;
+            }
+        }
+
+        // Impulses are instant, so only the first sub-step gets them. They are consumed even
+        // by bodies that ignore them.
+        if let Some(mut impulse) = impulse {
+            impulse.0 = Vec2::ZERO;
+        }
+    }
+}
+
+/// Wakes the sleeping bodies that are about to be moved by input, an external push,
+/// or a velocity set from outside the simulation.
+#[allow(clippy::type_complexity)]
+pub fn wake_disturbed_bodies(
+    cfg: Res<PhysicsConfig>,
+    mut query: Query<
+        (
+            Entity,
+            &AccumulatedInput,
+            &Velocity,
+            Option<&AngularVelocity>,
+            Option<&ExternalForce>,
+            Option<&ExternalImpulse>,
+            Option<&mut SleepTimer>,
+        ),
+        (With<Movable>, With<Sleeping>),
+    >,
+    mut commands: Commands,
+) {
+    for (entity, input, velocity, angular_velocity, force, impulse, timer) in &mut query {
+        let pushed = force.is_some_and(|f| f.force != Vec2::ZERO)
+            || impulse.is_some_and(|i| i.0 != Vec2::ZERO);
+        let resting = cfg.is_resting(velocity.0, angular_velocity.map_or(0.0, |w| w.0));
+        if input.0 != Vec2::ZERO || pushed || !resting {
+            // Woken bodies get a full `sleep_steps` before they may doze off again
+            if let Some(mut timer) = timer {
+                timer.0 = 0;
+            }
+            commands.entity(entity).remove::<Sleeping>();
+        }
+    }
+}
 
-        if acceleration.x < f32::EPSILON {
-            apply_drag_component(&mut velocity.x, drag_component);
+/// Counts how long every dynamic body has been still, then puts whole islands to sleep
+/// or wakes them up.
+///
+/// An island is a group of bodies linked by contacts or joints. It only falls asleep once all
+/// of its bodies have been still for [`PhysicsConfig::sleep_steps`], and as soon as one of them
+/// moves again the others wake up with it, so a stack never rests on a body that's moving away.
+///
+/// Only bodies with a [`SleepTimer`] ever sleep; the others keep their whole island awake.
+/// Sensor overlaps don't link islands, since a trigger zone holds nothing up.
+#[allow(clippy::type_complexity)]
+pub fn update_sleeping_bodies(
+    cfg: Res<PhysicsConfig>,
+    contacts: Res<Contacts>,
+    distances: Query<&DistanceJoint>,
+    springs: Query<&SpringJoint>,
+    drivers: Query<(&AccumulatedInput, Option<&ExternalForce>)>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Velocity,
+            Option<&mut AngularVelocity>,
+            Option<&mut SleepTimer>,
+            Option<&RigidBody>,
+            Has<Sensor>,
+            Has<Sleeping>,
+        ),
+        With<Movable>,
+    >,
+    mut commands: Commands,
+) {
+    let mut islands = Islands::default();
+    let mut sensors = HashSet::new();
+    for (entity, velocity, angular_velocity, timer, kind, sensor, _) in &mut query {
+        if !RigidBody::is_dynamic(kind) {
+            continue;
+        }
+        islands.insert(entity);
+        if sensor {
+            sensors.insert(entity);
         }
+        let Some(mut timer) = timer else {
+            continue;
+        };
+        // A body held in place by input or a force would only be woken again next step
+        let driven = drivers.get(entity).is_ok_and(|(input, force)| {
+            input.0 != Vec2::ZERO || force.is_some_and(|f| f.force != Vec2::ZERO)
+        });
+        if !driven && cfg.is_resting(velocity.0, angular_velocity.map_or(0.0, |w| w.0)) {
+            timer.0 = timer.0.saturating_add(1);
+        } else {
+            timer.0 = 0;
+        }
+    }
 
-        if acceleration.y < f32::EPSILON {
-            apply_drag_component(&mut velocity.y, drag_component);
+    for (a, b, _) in contacts.pairs() {
+        if !sensors.contains(&a) && !sensors.contains(&b) {
+            islands.union(a, b);
         }
+    }
+    for joint in &distances {
+        islands.union(joint.a, joint.b);
+    }
+    for spring in &springs {
+        islands.union(spring.a, spring.b);
+    }
 
-        velocity.0 += acceleration.0 * dt;
-        if velocity.0.length_squared() > max_speed_sq {
-            velocity.0 = velocity.normalize_or_zero() * cfg.max_speed;
+    // An island is ready to sleep only if none of its bodies moved recently
+    let mut ready: HashMap<Entity, bool> = HashMap::new();
+    for (entity, _, _, timer, _, _, _) in &query {
+        if let Some(root) = islands.find(entity) {
+            let still = cfg.sleep_steps > 0 && timer.is_some_and(|t| t.0 >= cfg.sleep_steps);
+            *ready.entry(root).or_insert(true) &= still;
         }
+    }
 
-        previous_physical_translation.0 = current_physical_translation.0;
-        current_physical_translation.0 += velocity.0 * dt;
+    for (entity, mut velocity, angular_velocity, timer, _, _, sleeping) in &mut query {
+        let Some(root) = islands.find(entity) else {
+            continue;
+        };
+        match (ready[&root], sleeping) {
+            (true, false) => {
+                velocity.0 = Vec3::ZERO;
+                if let Some(mut angular_velocity) = angular_velocity {
+                    angular_velocity.0 = 0.0;
+                }
+                commands.entity(entity).insert(Sleeping);
+            }
+            (false, true) => {
+                // Otherwise a body that never moved itself would doze off again next step
+                if let Some(mut timer) = timer {
+                    timer.0 = 0;
+                }
+                commands.entity(entity).remove::<Sleeping>();
+            }
+            _ => {}
+        }
+    }
+}
 
+/// Clears what the sub-steps of this fixed timestep have used up.
+pub fn consume_step_inputs(
+    mut query: Query<(&mut AccumulatedInput, Option<&mut ExternalForce>), With<Movable>>,
+) {
+    for (mut input, force) in query.iter_mut() {
         // Reset the input accumulator, as we are currently consuming all input that happened since the last fixed
         // timestep.
         *input = default();
+
+        if let Some(mut force) = force
+            && !force.persistent
+        {
+            force.force = Vec2::ZERO;
+        }
     }
 }
 
+/// Throws away the input held during a tick the simulation skipped, so it isn't applied
+/// all at once when the simulation steps or resumes.
+pub fn discard_skipped_inputs(mut query: Query<&mut AccumulatedInput, With<Movable>>) {
+    for mut input in query.iter_mut() {
+        *input = default();
+    }
+}
+
+/// Draws every body between its last two fixed steps. While the simulation is paused,
+/// bodies are drawn exactly where they stopped.
+#[allow(clippy::type_complexity)]
 pub fn interpolate_rendered_transform(
     fixed_time: Res<Time<Fixed>>,
+    physics_time: Res<PhysicsTime>,
     mut query: Query<(
         &mut Transform,
         &PhysicalTranslation,
         &PreviousPhysicalTranslation,
+        Option<(&PhysicalRotation, &PreviousPhysicalRotation)>,
     )>,
 ) {
-    for (mut transform, current_physical_translation, previous_physical_translation) in
+    for (mut transform, current_physical_translation, previous_physical_translation, rotation) in
         query.iter_mut()
     {
         let previous = previous_physical_translation.0;
         let current = current_physical_translation.0;
         // The overstep fraction is a value between 0 and 1 that tells us how far we are between two fixed timesteps.
-        let alpha = fixed_time.overstep_fraction();
+        let alpha = if physics_time.paused {
+            1.0
+        } else {
+            fixed_time.overstep_fraction()
+        };
 
         let rendered_translation = previous.lerp(current, alpha);
         transform.translation = rendered_translation;
+
+        if let Some((current_rotation, previous_rotation)) = rotation {
+            let previous = Quat::from_rotation_z(previous_rotation.0);
+            let current = Quat::from_rotation_z(current_rotation.0);
+            transform.rotation = previous.slerp(current, alpha);
+        }
     }
 }
 
@@ -88,8 +410,71 @@ pub fn interpolate_rendered_transform(
 
 #[inline(always)]
 fn apply_drag_component(v: &mut f32, drag_modulo: f32) {
-    if v.abs() < f32::EPSILON { return; }
+    if v.abs() < f32::EPSILON {
+        return;
+    }
 
     let delta = drag_modulo.min(v.abs());
     *v -= v.signum() * delta;
 }
+
+/// Summed force and drag override of every effector region containing `pos`.
+fn area_effects(
+    regions: &[EffectorRegion],
+    body: Entity,
+    pos: Vec2,
+    layers: CollisionLayers,
+) -> (Vec2, Option<f32>) {
+    let mut force = Vec2::ZERO;
+    let mut drag: Option<f32> = None;
+    for (entity, effector, collider, iso, region_layers) in regions {
+        if *entity == body
+            || !region_layers.interacts_with(&layers)
+            || !contains_point(&collider.0, *iso, pos)
+        {
+            continue;
+        }
+
+        force +=
+            effector.force + (iso.translation - pos).normalize_or_zero() * effector.radial_force;
+        if let Some(region_drag) = effector.drag {
+            drag = Some(drag.map_or(region_drag, |d| d.max(region_drag)));
+        }
+    }
+    (force, drag)
+}
+
+/// Union-find over the dynamic bodies, grouping them into islands.
+#[derive(Default)]
+struct Islands {
+    parent: HashMap<Entity, Entity>,
+}
+
+impl Islands {
+    fn insert(&mut self, entity: Entity) {
+        self.parent.insert(entity, entity);
+    }
+
+    /// The entity standing for `entity`'s island, or `None` if it was never inserted.
+    fn find(&mut self, entity: Entity) -> Option<Entity> {
+        let mut root = *self.parent.get(&entity)?;
+        while self.parent[&root] != root {
+            root = self.parent[&root];
+        }
+
+        // Point the whole path straight at the root, so later lookups are quick
+        let mut current = entity;
+        while current != root {
+            current = self.parent.insert(current, root).unwrap_or(root);
+        }
+        Some(root)
+    }
+
+    /// Merges the islands of `a` and `b`. Does nothing if either isn't a dynamic body,
+    /// so walls and kinematic bodies never link two islands together.
+    fn union(&mut self, a: Entity, b: Entity) {
+        if let (Some(a), Some(b)) = (self.find(a), self.find(b)) {
+            self.parent.insert(a, b);
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub struct CharacterBundle {
 pub struct EnemyBundle {
     character: CharacterBundle,
     tag: Enemy,
+    sleep_timer: SleepTimer, // idle enemies fall asleep, the player never does
 }
 
 #[derive(Bundle)]
@@ -43,6 +44,7 @@ impl EnemyBundle {
         Self {
             character: CharacterBundle::new("Enemy", meshes, materials.enemy.clone(), pos),
             tag: Enemy,
+            sleep_timer: SleepTimer::default(),
         }
     }
 }
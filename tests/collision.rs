@@ -0,0 +1,497 @@
+mod common;
+
+use common::*;
+use rustgamedev::prelude::*;
+use std::f32::consts::FRAC_PI_4;
+
+#[test]
+fn only_neighbours_reach_the_narrowphase() {
+    let mut app = app();
+    let a = ball(&mut app, Vec2::new(-600.0, 0.0), Vec2::ZERO, 40.0);
+    let b = ball(&mut app, Vec2::new(-580.0, 0.0), Vec2::ZERO, 40.0);
+    let c = ball(&mut app, Vec2::new(0.0, 0.0), Vec2::ZERO, 40.0);
+    let d = ball(&mut app, Vec2::new(75.0, 0.0), Vec2::ZERO, 40.0);
+    ball(&mut app, Vec2::new(600.0, 600.0), Vec2::ZERO, 40.0);
+    let mut cursor = event_cursor(&app);
+    step(&mut app);
+
+    let mut expected = vec![pair(a, b), pair(c, d)];
+    expected.sort();
+    assert_eq!(started_pairs(&app, &mut cursor), expected);
+    // Pushed apart, half each
+    assert!((pos(&app, a).distance(pos(&app, b)) - 80.0).abs() < 1e-3);
+    assert!((pos(&app, c).x + 2.5).abs() < 1e-3);
+}
+
+#[test]
+fn walls_are_found_through_the_grid_as_they_change() {
+    let mut app = app();
+    let near = wall(&mut app, Vec2::new(100.0, 0.0), Vec2::new(20.0, 200.0));
+    wall(&mut app, Vec2::new(1000.0, 0.0), Vec2::new(20.0, 200.0));
+    let touching = ball(&mut app, Vec2::new(60.0, 0.0), Vec2::ZERO, 40.0);
+    let mut cursor = event_cursor(&app);
+    step(&mut app);
+    assert_eq!(started_pairs(&app, &mut cursor), vec![pair(touching, near)]);
+    assert!(pos(&app, touching).x <= 50.0 + 1e-3);
+
+    // Moved onto another ball, the wall is found at its new place
+    let other = ball(&mut app, Vec2::new(-500.0, 0.0), Vec2::ZERO, 10.0);
+    app.world_mut()
+        .get_mut::<Transform>(near)
+        .unwrap()
+        .translation
+        .x = -500.0;
+    step(&mut app);
+    assert_eq!(started_pairs(&app, &mut cursor), vec![pair(other, near)]);
+
+    // Once despawned, nothing hits it any more
+    app.world_mut().despawn(near);
+    app.world_mut().despawn(other);
+    let last = ball(&mut app, Vec2::new(-500.0, 0.0), Vec2::ZERO, 10.0);
+    step(&mut app);
+    assert!(started_pairs(&app, &mut cursor).is_empty());
+    assert_eq!(pos(&app, last), Vec2::new(-500.0, 0.0));
+
+    // Plain colliders join the grid once they become a wall or a static body
+    let [late_wall, late_static] = [Vec2::new(0.0, 500.0), Vec2::new(0.0, -500.0)].map(|at| {
+        app.world_mut()
+            .spawn((
+                Transform::from_translation(at.extend(0.0)),
+                Collider(ColliderShape::Circle { radius: 10.0 }),
+            ))
+            .id()
+    });
+    step(&mut app);
+    app.world_mut().entity_mut(late_wall).insert(Wall);
+    app.world_mut()
+        .entity_mut(late_static)
+        .insert(RigidBody::Static);
+    let above = ball(&mut app, Vec2::new(0.0, 515.0), Vec2::ZERO, 10.0);
+    let below = ball(&mut app, Vec2::new(0.0, -515.0), Vec2::ZERO, 10.0);
+    step(&mut app);
+    let mut expected = vec![pair(above, late_wall), pair(below, late_static)];
+    expected.sort();
+    assert_eq!(started_pairs(&app, &mut cursor), expected);
+}
+
+#[test]
+fn ccd_stops_fast_circles_at_thin_walls() {
+    for ccd in [false, true] {
+        let mut app = app();
+        frictionless(&mut app);
+        let wall = wall(&mut app, Vec2::new(30.0, 0.0), Vec2::new(2.0, 100.0));
+        // 100 px per tick, far more than the wall is thick
+        let bullet = ball(&mut app, Vec2::ZERO, Vec2::new(6400.0, 0.0), 5.0);
+        if ccd {
+            app.world_mut().entity_mut(bullet).insert(Ccd);
+        }
+        let mut cursor = event_cursor(&app);
+        step(&mut app);
+
+        if ccd {
+            assert!((pos(&app, bullet).x - (29.0 - 5.0 - CCD_SKIN)).abs() < 1e-3);
+            assert_eq!(vel(&app, bullet), Vec2::new(-6400.0, 0.0));
+            assert_eq!(started_pairs(&app, &mut cursor), vec![pair(bullet, wall)]);
+        } else {
+            assert_eq!(pos(&app, bullet).x, 100.0);
+            assert!(started_pairs(&app, &mut cursor).is_empty());
+        }
+    }
+}
+
+#[test]
+fn materials_set_bounce_and_friction() {
+    // (wall material, velocity after the hit, combined restitution, combined friction)
+    let cases = [
+        (
+            PhysicsMaterial::default(),
+            Vec2::new(-100.0, 100.0),
+            1.0,
+            0.0,
+        ),
+        (
+            PhysicsMaterial::new(0.0, 1.0).with_restitution_combine(CombineRule::Min),
+            Vec2::new(0.0, 50.0),
+            0.0,
+            0.5,
+        ),
+        (
+            PhysicsMaterial::new(0.5, 0.2),
+            Vec2::new(-75.0, 82.5),
+            0.75,
+            0.1,
+        ),
+    ];
+    for (material, expected, restitution, friction) in cases {
+        let mut app = app();
+        app.world_mut().resource_mut::<PhysicsConfig>().drag = 0.0;
+        let wall = wall(&mut app, Vec2::new(30.0, 0.0), Vec2::new(20.0, 100.0));
+        app.world_mut().entity_mut(wall).insert(material);
+        let b = ball(
+            &mut app,
+            Vec2::new(12.0, 0.0),
+            Vec2::new(100.0, 100.0),
+            10.0,
+        );
+        step(&mut app);
+
+        assert!(
+            vel(&app, b).abs_diff_eq(expected, 1e-3),
+            "{material:?} {:?}",
+            vel(&app, b)
+        );
+        let contact = contacts(&app).get(b, wall).unwrap();
+        assert!((contact.restitution - restitution).abs() < 1e-6);
+        assert!((contact.friction - friction).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn ccd_stops_fast_circles_at_thin_walls_of_any_shape() {
+    for (shape, face) in [
+        (
+            ColliderShape::Polygon(PolygonHull::rect(Vec2::new(1.0, 50.0))),
+            29.0,
+        ),
+        (ColliderShape::Circle { radius: 2.0 }, 28.0),
+    ] {
+        let mut app = app();
+        frictionless(&mut app);
+        let wall = app
+            .world_mut()
+            .spawn((Wall, Transform::from_xyz(30.0, 0.0, 0.0), Collider(shape)))
+            .id();
+        let bullet = ball(&mut app, Vec2::ZERO, Vec2::new(6400.0, 0.0), 5.0);
+        app.world_mut().entity_mut(bullet).insert(Ccd);
+        let mut cursor = event_cursor(&app);
+        step(&mut app);
+
+        assert!((pos(&app, bullet).x - (face - 5.0 - CCD_SKIN)).abs() < 1e-3);
+        assert!(vel(&app, bullet).x < 0.0);
+        assert_eq!(started_pairs(&app, &mut cursor), vec![pair(bullet, wall)]);
+    }
+}
+
+#[test]
+fn polygons_collide_with_circles_and_each_other() {
+    let mut app = app();
+    app.world_mut().resource_mut::<PhysicsConfig>().drag = 0.0;
+    let triangle = PolygonHull::new(&[
+        Vec2::new(0.0, 50.0),
+        Vec2::new(-50.0, -50.0),
+        Vec2::new(50.0, -50.0),
+    ])
+    .unwrap();
+    app.world_mut().spawn((
+        Wall,
+        Transform::default(),
+        Collider(ColliderShape::Polygon(triangle)),
+    ));
+
+    // Dropped onto the tip, it bounces straight back up
+    let on_tip = ball(&mut app, Vec2::new(0.0, 58.0), Vec2::new(0.0, -64.0), 10.0);
+    step(&mut app);
+    assert!(pos(&app, on_tip).abs_diff_eq(Vec2::new(0.0, 60.0), 1e-3));
+    assert!(vel(&app, on_tip).abs_diff_eq(Vec2::new(0.0, 64.0), 1e-3));
+
+    // Overlapping the right edge, it is pushed out along that edge's normal
+    let start = Vec2::new(30.0, 10.0);
+    let on_edge = ball(&mut app, start, Vec2::ZERO, 10.0);
+    step(&mut app);
+    let push = (pos(&app, on_edge) - start).normalize();
+    assert!(
+        push.abs_diff_eq(Vec2::new(2.0, 1.0).normalize(), 1e-3),
+        "{push:?}"
+    );
+
+    // A moving polygon and a circle share the correction
+    let square = app
+        .world_mut()
+        .spawn((
+            MovableBundle {
+                phy_translation: PhysicalTranslation(Vec3::new(300.0, 300.0, 0.0)),
+                ..default()
+            },
+            Collider(ColliderShape::Polygon(PolygonHull::rect(Vec2::splat(20.0)))),
+            Mass::default(),
+        ))
+        .id();
+    let next_to_it = ball(&mut app, Vec2::new(325.0, 300.0), Vec2::ZERO, 10.0);
+    step(&mut app);
+    assert!(pos(&app, square).abs_diff_eq(Vec2::new(297.5, 300.0), 1e-3));
+    assert!(pos(&app, next_to_it).abs_diff_eq(Vec2::new(327.5, 300.0), 1e-3));
+}
+
+#[test]
+fn moving_rectangles_hit_walls_and_circles_swap_velocities() {
+    let mut app = app();
+    app.world_mut().resource_mut::<PhysicsConfig>().drag = 0.0;
+    wall(&mut app, Vec2::new(100.0, 0.0), Vec2::new(20.0, 200.0));
+    let crate_ = app
+        .world_mut()
+        .spawn((
+            MovableBundle {
+                phy_translation: PhysicalTranslation(Vec3::new(75.0, 0.0, 0.0)),
+                velocity: Velocity(Vec3::new(64.0, 0.0, 0.0)),
+                ..default()
+            },
+            Collider(ColliderShape::Rect {
+                half_extents: Vec2::splat(20.0),
+            }),
+            Mass::default(),
+        ))
+        .id();
+    let a = ball(&mut app, Vec2::new(-300.0, 0.0), Vec2::new(64.0, 0.0), 10.0);
+    let b = ball(
+        &mut app,
+        Vec2::new(-280.0, 0.0),
+        Vec2::new(-64.0, 0.0),
+        10.0,
+    );
+    step(&mut app);
+
+    assert!(pos(&app, crate_).abs_diff_eq(Vec2::new(70.0, 0.0), 1e-3));
+    assert_eq!(vel(&app, crate_), Vec2::new(-64.0, 0.0));
+    assert_eq!(vel(&app, a), Vec2::new(-64.0, 0.0));
+    assert_eq!(vel(&app, b), Vec2::new(64.0, 0.0));
+    assert!((contacts(&app).get(a, b).unwrap().impulse - 128.0).abs() < 1e-3);
+}
+
+#[test]
+fn off_centre_hits_spin_bodies_and_rotated_walls_deflect() {
+    let mut app = app();
+    wall(&mut app, Vec2::new(0.0, -100.0), Vec2::new(1000.0, 20.0));
+    let plank = app
+        .world_mut()
+        .spawn((
+            MovableBundle {
+                velocity: Velocity(Vec3::new(0.0, -300.0, 0.0)),
+                ..default()
+            },
+            RotationBundle {
+                phy_rotation: PhysicalRotation(0.3),
+                prev_phy_rotation: PreviousPhysicalRotation(0.3),
+                ..default()
+            },
+            Transform::default(),
+            Collider(ColliderShape::Rect {
+                half_extents: Vec2::new(40.0, 10.0),
+            }),
+            Mass::default(),
+        ))
+        .id();
+    let mut fastest_spin: f32 = 0.0;
+    for _ in 0..40 {
+        step(&mut app);
+        let spin = app.world().get::<AngularVelocity>(plank).unwrap().0;
+        fastest_spin = fastest_spin.max(spin.abs());
+    }
+    assert!(fastest_spin > 0.5);
+    assert!(pos(&app, plank).y > -100.0);
+
+    let mut app = common::app();
+    app.world_mut().spawn((
+        Wall,
+        Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+        Collider(ColliderShape::Rect {
+            half_extents: Vec2::new(200.0, 10.0),
+        }),
+    ));
+    let dropped = ball(
+        &mut app,
+        Vec2::new(0.0, 100.0),
+        Vec2::new(0.0, -300.0),
+        10.0,
+    );
+    steps(&mut app, 40);
+    assert!(vel(&app, dropped).x.abs() > 100.0);
+}
+
+#[test]
+fn layers_decide_who_collides() {
+    let mut app = app();
+    wall(&mut app, Vec2::new(0.0, -50.0), Vec2::new(400.0, 20.0));
+    let ghost = ball(&mut app, Vec2::ZERO, Vec2::new(0.0, -300.0), 10.0);
+    app.world_mut()
+        .entity_mut(ghost)
+        .insert(CollisionLayers::new(0b10, CollisionLayers::NONE));
+    // `a` ignores `b`'s layer, so they pass through each other
+    let a = ball(&mut app, Vec2::new(300.0, 0.0), Vec2::new(200.0, 0.0), 10.0);
+    let b = ball(
+        &mut app,
+        Vec2::new(400.0, 0.0),
+        Vec2::new(-200.0, 0.0),
+        10.0,
+    );
+    app.world_mut()
+        .entity_mut(a)
+        .insert(CollisionLayers::new(0b100, 0b1));
+    app.world_mut()
+        .entity_mut(b)
+        .insert(CollisionLayers::new(0b1, 0b1));
+    let mut cursor = event_cursor(&app);
+    steps(&mut app, 30);
+
+    assert!(pos(&app, ghost).y < -70.0);
+    assert!(pos(&app, a).x > pos(&app, b).x);
+    assert!(started_pairs(&app, &mut cursor).is_empty());
+}
+
+#[test]
+fn sensors_report_overlaps_without_pushing() {
+    let mut app = app();
+    app.world_mut().spawn((
+        Sensor,
+        Transform::from_xyz(0.0, -50.0, 0.0),
+        Collider(ColliderShape::Rect {
+            half_extents: Vec2::new(200.0, 10.0),
+        }),
+    ));
+    let falling = ball(&mut app, Vec2::ZERO, Vec2::new(0.0, -300.0), 10.0);
+    let a = ball(&mut app, Vec2::new(300.0, 0.0), Vec2::new(200.0, 0.0), 10.0);
+    let b = ball(
+        &mut app,
+        Vec2::new(400.0, 0.0),
+        Vec2::new(-200.0, 0.0),
+        10.0,
+    );
+    app.world_mut().entity_mut(b).insert(Sensor);
+
+    let mut cursor = event_cursor(&app);
+    let (mut started, mut stopped) = (0, 0);
+    for _ in 0..30 {
+        step(&mut app);
+        read_events(&app, &mut cursor, |event| {
+            if let CollisionEvent::Started { impulse, .. } = *event {
+                assert_eq!(impulse, 0.0);
+                started += 1;
+            }
+            if matches!(event, CollisionEvent::Stopped { .. }) {
+                stopped += 1;
+            }
+        });
+    }
+
+    assert!(pos(&app, falling).y < -70.0);
+    assert!(pos(&app, a).x > pos(&app, b).x);
+    assert_eq!((started, stopped), (2, 2));
+}
+
+#[test]
+fn kinematic_bodies_push_and_static_ones_stay_put() {
+    let mut app = app();
+    let lift = app
+        .world_mut()
+        .spawn((
+            MovableBundle {
+                velocity: Velocity(Vec3::new(0.0, 100.0, 0.0)),
+                ..default()
+            },
+            RigidBody::Kinematic,
+            Mass::default(),
+            Transform::default(),
+            Collider(ColliderShape::Rect {
+                half_extents: Vec2::new(50.0, 10.0),
+            }),
+        ))
+        .id();
+    let rider = ball(&mut app, Vec2::new(0.0, 25.0), Vec2::ZERO, 10.0);
+    wall(&mut app, Vec2::new(0.0, 500.0), Vec2::new(200.0, 20.0));
+    let post = ball(
+        &mut app,
+        Vec2::new(300.0, 0.0),
+        Vec2::new(-100.0, 0.0),
+        10.0,
+    );
+    app.world_mut().entity_mut(post).insert(RigidBody::Static);
+    steps(&mut app, 64);
+
+    // The lift keeps its velocity, carries the rider up, and the post never moves
+    assert!((pos(&app, lift).y - 100.0).abs() < 1e-2);
+    assert_eq!(vel(&app, lift).y, 100.0);
+    assert!(pos(&app, rider).y > pos(&app, lift).y);
+    assert_eq!(pos(&app, post).x, 300.0);
+}
+
+/// Squeezes 16 balls into a box and returns the deepest overlap left between two of them.
+fn crowd_overlap(solver_iterations: u32, substeps: u32) -> f32 {
+    let mut app = app();
+    app.world_mut()
+        .resource_mut::<CollisionConfig>()
+        .solver_iterations = solver_iterations;
+    app.world_mut().resource_mut::<PhysicsConfig>().substeps = substeps;
+    for (at, size) in [
+        (Vec2::new(0.0, -110.0), Vec2::new(240.0, 20.0)),
+        (Vec2::new(0.0, 110.0), Vec2::new(240.0, 20.0)),
+        (Vec2::new(-110.0, 0.0), Vec2::new(20.0, 240.0)),
+        (Vec2::new(110.0, 0.0), Vec2::new(20.0, 240.0)),
+    ] {
+        wall(&mut app, at, size);
+    }
+    let balls: Vec<Entity> = (0..16)
+        .map(|i| {
+            let at = Vec2::new((i % 4) as f32 * 20.0 - 30.0, (i / 4) as f32 * 20.0 - 30.0);
+            let e = ball(&mut app, at, Vec2::ZERO, 22.0);
+            app.world_mut().entity_mut(e).insert((
+                PhysicsMaterial::new(0.0, 0.0),
+                ExternalForce::persistent(-at.normalize_or_zero() * 300.0),
+            ));
+            e
+        })
+        .collect();
+    steps(&mut app, 128);
+
+    let mut deepest: f32 = 0.0;
+    for (i, &a) in balls.iter().enumerate() {
+        for &b in &balls[i + 1..] {
+            deepest = deepest.max(44.0 - pos(&app, a).distance(pos(&app, b)));
+        }
+    }
+    deepest
+}
+
+#[test]
+fn iterations_and_substeps_settle_crowds() {
+    let single = crowd_overlap(1, 1);
+    let iterated = crowd_overlap(4, 1);
+    let substepped = crowd_overlap(8, 4);
+    assert!(iterated < single);
+    assert!(substepped < iterated);
+    assert!(iterated < 0.05, "{iterated}");
+    assert!(substepped < 1e-3, "{substepped}");
+}
+
+#[test]
+fn contact_graph_lists_neighbours_from_each_side() {
+    let mut app = app();
+    let a = ball(&mut app, Vec2::ZERO, Vec2::ZERO, 10.0);
+    let b = ball(&mut app, Vec2::new(15.0, 0.0), Vec2::ZERO, 10.0);
+    let c = ball(&mut app, Vec2::new(-15.0, 0.0), Vec2::ZERO, 10.0);
+    let far = ball(&mut app, Vec2::new(500.0, 0.0), Vec2::ZERO, 10.0);
+    step(&mut app);
+
+    let graph = contacts(&app);
+    assert!(graph.touching(a, b) && graph.touching(b, a));
+    assert!(!graph.touching(b, c) && !graph.touching(a, far));
+    let mut of_a: Vec<Entity> = graph.of(a).map(|(other, _)| other).collect();
+    of_a.sort();
+    let mut expected = vec![b, c];
+    expected.sort();
+    assert_eq!(of_a, expected);
+    assert_eq!(graph.of(far).count(), 0);
+    assert_eq!(graph.pairs().count(), 2);
+
+    // Normals point towards whoever asks
+    let (_, seen_by_a) = graph.of(a).find(|(other, _)| *other == b).unwrap();
+    assert!(seen_by_a.normal.x < 0.0);
+    assert!(graph.get(b, a).unwrap().normal.x > 0.0);
+
+    app.world_mut().despawn(b);
+    let graph = contacts(&app);
+    assert_eq!(
+        graph.of(a).map(|(other, _)| other).collect::<Vec<_>>(),
+        vec![c]
+    );
+    assert_eq!(graph.of(b).count(), 0);
+    assert_eq!(graph.pairs().count(), 1);
+}
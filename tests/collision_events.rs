@@ -0,0 +1,127 @@
+mod common;
+
+use common::*;
+use rustgamedev::prelude::*;
+
+/// A ball pressed onto a floor by a constant force, so it stays in contact.
+fn resting_ball(app: &mut App) -> (Entity, Entity) {
+    let floor = wall(app, Vec2::new(0.0, -30.0), Vec2::new(400.0, 20.0));
+    let ball = ball(app, Vec2::ZERO, Vec2::new(0.0, -50.0), 10.0);
+    app.world_mut().entity_mut(ball).insert((
+        PhysicsMaterial::new(0.0, 0.0).with_restitution_combine(CombineRule::Min),
+        ExternalForce::persistent(Vec2::new(0.0, -500.0)),
+    ));
+    (floor, ball)
+}
+
+#[test]
+fn contacts_start_persist_and_stop_when_despawned() {
+    let mut app = app();
+    let (floor, ball) = resting_ball(&mut app);
+    let mut cursor = event_cursor(&app);
+    let mut events = Vec::new();
+    for _ in 0..40 {
+        step(&mut app);
+        events.extend(read_events(&app, &mut cursor, |event| match *event {
+            CollisionEvent::Started { a, b, .. } => ("started", a, b, 0.0),
+            CollisionEvent::Persisting { a, b, duration, .. } => ("persisting", a, b, duration),
+            CollisionEvent::Stopped { a, b, .. } => ("stopped", a, b, 0.0),
+        }));
+    }
+
+    let pair = |a: Entity, b: Entity| (a == ball && b == floor) || (a == floor && b == ball);
+    assert!(events.iter().all(|&(_, a, b, _)| pair(a, b)));
+    assert_eq!(events[0].0, "started");
+    assert!(events[1..].iter().all(|&(kind, ..)| kind == "persisting"));
+    // Durations keep growing while the contact lasts
+    assert!(events.windows(2).skip(1).all(|w| w[1].3 > w[0].3));
+
+    app.world_mut().despawn(ball);
+    let stopped = read_events(&app, &mut cursor, |event| match *event {
+        CollisionEvent::Stopped { reason, .. } => Some(reason),
+        _ => None,
+    });
+    assert_eq!(stopped, vec![Some(StopReason::Despawned)]);
+    assert_eq!(contacts(&app).pairs().count(), 0);
+
+    // The next step doesn't report the removed pair a second time
+    step(&mut app);
+    assert!(read_events(&app, &mut cursor, |_| ()).is_empty());
+}
+
+#[derive(Resource, Default)]
+struct Seen(Vec<&'static str>);
+
+#[test]
+fn observers_hear_about_their_own_contacts() {
+    let mut app = app();
+    app.init_resource::<Seen>();
+    let (floor, ball) = resting_ball(&mut app);
+    app.world_mut().entity_mut(ball).observe(
+        move |trigger: On<CollisionStart>, mut seen: ResMut<Seen>| {
+            assert_eq!((trigger.entity, trigger.other), (ball, floor));
+            // Normals point towards the observed entity
+            assert!(trigger.data.normal.y > 0.0);
+            seen.0.push("ball started");
+        },
+    );
+    app.world_mut().entity_mut(floor).observe(
+        move |trigger: On<CollisionEnd>, mut seen: ResMut<Seen>| {
+            assert_eq!((trigger.entity, trigger.other), (floor, ball));
+            assert_eq!(trigger.reason, StopReason::Despawned);
+            seen.0.push("floor ended");
+        },
+    );
+    app.world_mut()
+        .add_observer(|_: On<CollisionEnd>, mut seen: ResMut<Seen>| seen.0.push("any ended"));
+    steps(&mut app, 20);
+    app.world_mut().despawn(ball);
+    app.world_mut().flush();
+    step(&mut app);
+
+    let seen = &app.world().resource::<Seen>().0;
+    let count = |what: &str| seen.iter().filter(|s| **s == what).count();
+    assert_eq!(count("ball started"), 1);
+    assert_eq!(count("floor ended"), 1);
+    assert_eq!(count("any ended"), 2);
+}
+
+#[derive(Resource, Default)]
+struct Pushed(Option<Entity>);
+
+fn push_down(mut pushed: ResMut<Pushed>, mut commands: Commands) {
+    if let Some(entity) = pushed.0.take() {
+        commands
+            .entity(entity)
+            .apply_impulse(Vec2::new(0.0, -640.0));
+    }
+}
+
+fn note_started(mut events: MessageReader<CollisionEvent>, mut seen: ResMut<Seen>) {
+    for event in events.read() {
+        if matches!(event, CollisionEvent::Started { .. }) {
+            seen.0.push("started");
+        }
+    }
+}
+
+#[test]
+fn systems_in_physics_sets_see_the_current_step() {
+    let mut app = app();
+    app.init_resource::<Seen>().init_resource::<Pushed>();
+    app.add_systems(
+        FixedUpdate,
+        (
+            push_down.in_set(PhysicsSet::PrePhysics),
+            note_started.in_set(PhysicsSet::PostPhysics),
+        ),
+    );
+    wall(&mut app, Vec2::new(0.0, -20.0), Vec2::new(400.0, 20.0));
+    let ball = ball(&mut app, Vec2::new(0.0, 5.0), Vec2::ZERO, 10.0);
+    app.world_mut().resource_mut::<Pushed>().0 = Some(ball);
+
+    // The impulse lands before the bodies move, and the hit is heard in the same tick
+    step(&mut app);
+    assert!(vel(&app, ball).y > 0.0);
+    assert_eq!(app.world().resource::<Seen>().0, vec!["started"]);
+}
@@ -0,0 +1,128 @@
+//! Helpers shared by the integration tests: a headless app running only the physics,
+//! stepped by hand one fixed tick at a time.
+#![allow(dead_code)]
+
+use bevy::ecs::message::MessageCursor;
+use rustgamedev::prelude::*;
+use std::time::Duration;
+
+/// Length of the fixed tick the tests step by.
+pub const DT: f32 = 1.0 / 64.0;
+
+pub fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).add_plugins(PhysicsPlugin);
+    app.finish();
+    app.cleanup();
+    app
+}
+
+/// Runs one fixed tick.
+pub fn step(app: &mut App) {
+    app.world_mut()
+        .resource_mut::<Time<Fixed>>()
+        .advance_by(Duration::from_secs_f32(DT));
+    app.world_mut().run_schedule(FixedUpdate);
+}
+
+pub fn steps(app: &mut App, count: usize) {
+    for _ in 0..count {
+        step(app);
+    }
+}
+
+/// A static rectangle centred on `pos`, `size` wide and high.
+pub fn wall(app: &mut App, pos: Vec2, size: Vec2) -> Entity {
+    app.world_mut()
+        .spawn((
+            Wall,
+            Transform::from_translation(pos.extend(0.0)),
+            Collider(ColliderShape::Rect {
+                half_extents: size * 0.5,
+            }),
+        ))
+        .id()
+}
+
+/// A dynamic circle of 1 kg.
+pub fn ball(app: &mut App, pos: Vec2, vel: Vec2, radius: f32) -> Entity {
+    app.world_mut()
+        .spawn((
+            MovableBundle {
+                phy_translation: PhysicalTranslation(pos.extend(0.0)),
+                velocity: Velocity(vel.extend(0.0)),
+                ..default()
+            },
+            Transform::from_translation(pos.extend(0.0)),
+            Collider(ColliderShape::Circle { radius }),
+            Mass::default(),
+        ))
+        .id()
+}
+
+pub fn pos(app: &App, entity: Entity) -> Vec2 {
+    app.world()
+        .get::<PhysicalTranslation>(entity)
+        .unwrap()
+        .truncate()
+}
+
+pub fn vel(app: &App, entity: Entity) -> Vec2 {
+    app.world().get::<Velocity>(entity).unwrap().truncate()
+}
+
+/// A cursor that only sees the `CollisionEvent`s written from now on.
+pub fn event_cursor(app: &App) -> MessageCursor<CollisionEvent> {
+    app.world()
+        .resource::<Messages<CollisionEvent>>()
+        .get_cursor_current()
+}
+
+/// Maps every `CollisionEvent` written since `cursor` last read.
+pub fn read_events<T>(
+    app: &App,
+    cursor: &mut MessageCursor<CollisionEvent>,
+    f: impl FnMut(&CollisionEvent) -> T,
+) -> Vec<T> {
+    let messages = app.world().resource::<Messages<CollisionEvent>>();
+    cursor.read(messages).map(f).collect()
+}
+
+/// The pairs that started touching since `cursor` last read, each sorted and the list too.
+pub fn started_pairs(
+    app: &App,
+    cursor: &mut MessageCursor<CollisionEvent>,
+) -> Vec<(Entity, Entity)> {
+    let mut pairs: Vec<(Entity, Entity)> = read_events(app, cursor, |event| match *event {
+        CollisionEvent::Started { a, b, .. } => Some((a.min(b), a.max(b))),
+        _ => None,
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    pairs.sort();
+    pairs
+}
+
+/// Sorts a pair the way [`started_pairs`] does.
+pub fn pair(a: Entity, b: Entity) -> (Entity, Entity) {
+    (a.min(b), a.max(b))
+}
+
+/// Turns off drag and the speed cap, so bodies keep whatever velocity they are given.
+pub fn frictionless(app: &mut App) {
+    let mut cfg = app.world_mut().resource_mut::<PhysicsConfig>();
+    cfg.drag = 0.0;
+    cfg.max_speed = 1e6;
+}
+
+pub fn contacts(app: &App) -> &Contacts {
+    app.world().resource::<Contacts>()
+}
+
+/// Queues commands against the app's world and applies them right away.
+pub fn with_commands(app: &mut App, f: impl FnOnce(&mut Commands)) {
+    let world = app.world_mut();
+    f(&mut world.commands());
+    world.flush();
+}
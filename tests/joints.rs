@@ -0,0 +1,32 @@
+mod common;
+
+use common::*;
+use rustgamedev::prelude::*;
+
+#[test]
+fn joints_hold_their_bodies_together() {
+    let mut app = app();
+    app.world_mut().resource_mut::<PhysicsConfig>().drag = 0.0;
+    // A rigid rod, flung apart
+    let a = ball(&mut app, Vec2::ZERO, Vec2::new(-200.0, 50.0), 5.0);
+    let b = ball(&mut app, Vec2::new(100.0, 0.0), Vec2::new(200.0, 0.0), 5.0);
+    app.world_mut().spawn(DistanceJoint::new(a, b, 100.0));
+    // A pendulum swinging around a pin
+    let pin = Vec2::new(1000.0, 200.0);
+    let bob = ball(&mut app, Vec2::new(1000.0, 0.0), Vec2::new(0.0, 300.0), 5.0);
+    app.world_mut()
+        .spawn(PinJoint::new(bob, pin).with_length(150.0));
+    // A stretched spring
+    let c = ball(&mut app, Vec2::new(-1000.0, 0.0), Vec2::ZERO, 5.0);
+    let d = ball(&mut app, Vec2::new(-800.0, 0.0), Vec2::ZERO, 5.0);
+    app.world_mut()
+        .spawn(SpringJoint::new(c, d, 50.0, 20.0, 4.0));
+
+    for _ in 0..400 {
+        step(&mut app);
+        assert!((pos(&app, a).distance(pos(&app, b)) - 100.0).abs() < 0.5);
+        assert!((pos(&app, bob).distance(pin) - 150.0).abs() < 0.5);
+    }
+    // The spring settles near its rest length
+    assert!((pos(&app, c).distance(pos(&app, d)) - 50.0).abs() < 5.0);
+}
@@ -0,0 +1,298 @@
+mod common;
+
+use common::*;
+use rustgamedev::prelude::*;
+
+/// Holds each entity's input in its direction for `steps` ticks.
+fn hold_input(app: &mut App, inputs: &[(Entity, Vec2)], steps: usize) {
+    for _ in 0..steps {
+        for &(entity, direction) in inputs {
+            app.world_mut()
+                .get_mut::<AccumulatedInput>(entity)
+                .unwrap()
+                .0 = direction;
+        }
+        step(app);
+    }
+}
+
+#[test]
+fn movement_stats_override_the_config_per_body() {
+    let mut app = app();
+    let regular = ball(&mut app, Vec2::ZERO, Vec2::ZERO, 5.0);
+    let slow = ball(&mut app, Vec2::new(0.0, 100.0), Vec2::ZERO, 5.0);
+    let stats = app.world().resource::<PhysicsConfig>().movement_stats();
+    app.world_mut().entity_mut(slow).insert(MovementStats {
+        max_speed: 100.0,
+        ..stats
+    });
+    hold_input(&mut app, &[(regular, Vec2::X), (slow, Vec2::X)], 64);
+
+    let max_speed = app.world().resource::<PhysicsConfig>().max_speed;
+    assert!((vel(&app, regular).length() - max_speed).abs() < 1e-2);
+    assert!((vel(&app, slow).length() - 100.0).abs() < 1e-2);
+}
+
+#[test]
+fn impulses_and_forces_push_bodies() {
+    let mut app = app();
+    let pushed = ball(&mut app, Vec2::ZERO, Vec2::ZERO, 10.0);
+    app.world_mut().entity_mut(pushed).insert(Mass(2.0));
+    with_commands(&mut app, |commands| {
+        commands.entity(pushed).apply_impulse(Vec2::new(400.0, 0.0));
+    });
+    step(&mut app);
+
+    // 400 Ns on 2 kg, and the impulse is used up
+    let launched = vel(&app, pushed);
+    assert!((launched.x - 200.0).abs() < 1e-3);
+    assert_eq!(
+        app.world().get::<ExternalImpulse>(pushed).unwrap().0,
+        Vec2::ZERO
+    );
+    step(&mut app);
+    assert!(vel(&app, pushed).x < launched.x);
+
+    app.world_mut()
+        .entity_mut(pushed)
+        .insert(ExternalForce::persistent(Vec2::new(0.0, 2000.0)));
+    steps(&mut app, 10);
+    assert!(vel(&app, pushed).y > 100.0);
+    assert_eq!(
+        app.world().get::<ExternalForce>(pushed).unwrap().force.y,
+        2000.0
+    );
+
+    // Knockbacks aren't capped by the walking speed, only slowed by drag afterwards
+    let max_speed = app.world().resource::<PhysicsConfig>().max_speed;
+    let blasted = ball(&mut app, Vec2::new(0.0, 500.0), Vec2::ZERO, 10.0);
+    with_commands(&mut app, |commands| {
+        commands
+            .entity(blasted)
+            .apply_impulse(Vec2::new(-3.0 * max_speed, 0.0));
+    });
+    step(&mut app);
+    assert!((vel(&app, blasted).x + 3.0 * max_speed).abs() < 1e-3);
+    steps(&mut app, 4);
+    let slowing = vel(&app, blasted).length();
+    assert!(slowing > max_speed && slowing < 3.0 * max_speed);
+}
+
+#[test]
+fn drag_modes_slow_bodies_down() {
+    for mode in [
+        DragMode::ConstantDeceleration,
+        DragMode::Linear,
+        DragMode::Quadratic,
+    ] {
+        let mut app = app();
+        {
+            let mut cfg = app.world_mut().resource_mut::<PhysicsConfig>();
+            cfg.drag_mode = mode;
+            cfg.drag = match mode {
+                DragMode::ConstantDeceleration => 100.0,
+                DragMode::Linear => 1.0,
+                DragMode::Quadratic => 0.01,
+            };
+        }
+        let coasting = ball(&mut app, Vec2::ZERO, Vec2::new(200.0, 0.0), 5.0);
+        let mut last = vel(&app, coasting).x;
+        for _ in 0..32 {
+            step(&mut app);
+            let speed = vel(&app, coasting).x;
+            assert!(speed < last && speed > 0.0, "{mode:?} {speed}");
+            last = speed;
+        }
+    }
+}
+
+#[test]
+fn releasing_input_brakes_the_same_in_every_direction() {
+    let mut app = app();
+    let directions = [Vec2::NEG_X, Vec2::NEG_Y, Vec2::X, Vec2::Y, Vec2::ONE];
+    // Far enough apart that nobody bumps into anybody
+    let inputs: Vec<(Entity, Vec2)> = directions
+        .iter()
+        .enumerate()
+        .map(|(i, &dir)| {
+            let at = Vec2::new(i as f32 * 1000.0, 0.0);
+            (ball(&mut app, at, Vec2::ZERO, 5.0), dir)
+        })
+        .collect();
+    hold_input(&mut app, &inputs, 32);
+
+    let [left, down, right, up, diagonal] = [0, 1, 2, 3, 4].map(|i| inputs[i].0);
+    let heading = Vec2::ONE.normalize();
+    let mut last = vel(&app, diagonal).length();
+    for _ in 0..32 {
+        step(&mut app);
+        assert!((vel(&app, left) + vel(&app, right)).length() < 1e-4);
+        assert!((vel(&app, down) + vel(&app, up)).length() < 1e-4);
+        assert!((vel(&app, right).x - vel(&app, up).y).abs() < 1e-4);
+
+        // Slows down along the way it was going instead of drifting onto an axis
+        let velocity = vel(&app, diagonal);
+        let speed = velocity.length();
+        assert!(speed < last || speed == 0.0);
+        if speed > 0.0 {
+            assert!(velocity.normalize().dot(heading) > 1.0 - 1e-5);
+        }
+        last = speed;
+    }
+}
+
+#[test]
+fn area_effectors_push_what_overlaps_them() {
+    let mut app = app();
+    app.world_mut().resource_mut::<PhysicsConfig>().drag = 0.0;
+    app.world_mut().spawn((
+        AreaEffector::directional(Vec2::new(0.0, 100.0)),
+        Collider(ColliderShape::Rect {
+            half_extents: Vec2::splat(100.0),
+        }),
+        Transform::from_xyz(500.0, 0.0, 0.0),
+    ));
+    app.world_mut().spawn((
+        AreaEffector::radial(200.0),
+        Collider(ColliderShape::Circle { radius: 300.0 }),
+        Transform::from_xyz(-1000.0, 0.0, 0.0),
+    ));
+    app.world_mut().spawn((
+        AreaEffector::drag(1000.0),
+        Collider(ColliderShape::Circle { radius: 100.0 }),
+        Transform::from_xyz(0.0, 1000.0, 0.0),
+    ));
+    let windy = ball(&mut app, Vec2::new(500.0, 0.0), Vec2::ZERO, 5.0);
+    let outside = ball(&mut app, Vec2::new(0.0, -500.0), Vec2::new(50.0, 0.0), 5.0);
+    let pulled = ball(&mut app, Vec2::new(-800.0, 0.0), Vec2::ZERO, 5.0);
+    let stuck = ball(&mut app, Vec2::new(0.0, 1000.0), Vec2::new(100.0, 0.0), 5.0);
+    steps(&mut app, 10);
+
+    assert!(vel(&app, windy).y > 10.0 && vel(&app, windy).x.abs() < 1e-4);
+    assert_eq!(vel(&app, outside), Vec2::new(50.0, 0.0));
+    assert!(vel(&app, pulled).x < -10.0);
+    assert_eq!(vel(&app, stuck), Vec2::ZERO);
+
+    // Strong enough wind blows a body past its walking speed
+    app.world_mut().spawn((
+        AreaEffector::directional(Vec2::new(64_000.0, 0.0)),
+        Collider(ColliderShape::Circle { radius: 50.0 }),
+        Transform::from_xyz(0.0, -2000.0, 0.0),
+    ));
+    let gust = ball(&mut app, Vec2::new(0.0, -2000.0), Vec2::ZERO, 5.0);
+    step(&mut app);
+    let max_speed = app.world().resource::<PhysicsConfig>().max_speed;
+    assert!(vel(&app, gust).x > max_speed);
+
+    // Regions follow their simulated position, not the interpolated `Transform`
+    let drifting = app
+        .world_mut()
+        .spawn((
+            AreaEffector::directional(Vec2::new(0.0, 100.0)),
+            Collider(ColliderShape::Circle { radius: 50.0 }),
+            Transform::from_xyz(1000.0, -3000.0, 0.0),
+            PhysicalTranslation::default(),
+        ))
+        .id();
+    app.world_mut()
+        .get_mut::<PhysicalTranslation>(drifting)
+        .unwrap()
+        .x = 0.0;
+    let caught = ball(&mut app, Vec2::new(0.0, -3000.0), Vec2::ZERO, 5.0);
+    let missed = ball(&mut app, Vec2::new(1000.0, -3000.0), Vec2::ZERO, 5.0);
+    step(&mut app);
+    assert!(vel(&app, caught).y > 0.0);
+    assert_eq!(vel(&app, missed), Vec2::ZERO);
+}
+
+#[test]
+fn bodies_start_at_their_transform_and_teleport_without_sliding() {
+    let mut app = app();
+    let spawned = app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(40.0, -30.0, 5.0),
+            MovableBundle::default(),
+        ))
+        .id();
+    let world = app.world();
+    assert_eq!(
+        world.get::<PhysicalTranslation>(spawned).unwrap().0,
+        Vec3::new(40.0, -30.0, 5.0)
+    );
+    assert_eq!(
+        world.get::<PreviousPhysicalTranslation>(spawned).unwrap().0,
+        Vec3::new(40.0, -30.0, 5.0)
+    );
+
+    // Inserting it again on a live body keeps the given value
+    app.world_mut()
+        .entity_mut(spawned)
+        .insert(PhysicalTranslation(Vec3::new(0.0, 80.0, 5.0)));
+    assert_eq!(
+        app.world().get::<PhysicalTranslation>(spawned).unwrap().0,
+        Vec3::new(0.0, 80.0, 5.0)
+    );
+
+    let portal = ball(&mut app, Vec2::ZERO, Vec2::new(100.0, 0.0), 5.0);
+    let respawn = ball(&mut app, Vec2::new(0.0, 100.0), Vec2::new(100.0, 0.0), 5.0);
+    with_commands(&mut app, |commands| {
+        commands
+            .entity(portal)
+            .teleport(Teleport::to(Vec2::new(500.0, 500.0)));
+        commands
+            .entity(respawn)
+            .teleport(Teleport::stopped(Vec2::new(-500.0, 500.0)));
+    });
+
+    let world = app.world();
+    assert_eq!(pos(&app, portal), Vec2::new(500.0, 500.0));
+    assert_eq!(
+        world.get::<PreviousPhysicalTranslation>(portal).unwrap().0,
+        Vec3::new(500.0, 500.0, 0.0)
+    );
+    assert_eq!(
+        world.get::<Transform>(portal).unwrap().translation,
+        Vec3::new(500.0, 500.0, 0.0)
+    );
+    assert_eq!(vel(&app, portal), Vec2::new(100.0, 0.0));
+    assert_eq!(pos(&app, respawn), Vec2::new(-500.0, 500.0));
+    assert_eq!(vel(&app, respawn), Vec2::ZERO);
+}
+
+#[test]
+fn physics_time_pauses_steps_and_slows_down() {
+    let mut app = app();
+    frictionless(&mut app);
+    let coasting = ball(&mut app, Vec2::ZERO, Vec2::new(64.0, 0.0), 5.0);
+
+    app.world_mut().resource_mut::<PhysicsTime>().pause();
+    steps(&mut app, 10);
+    assert_eq!(pos(&app, coasting), Vec2::ZERO);
+
+    with_commands(&mut app, |commands| commands.queue(StepPhysics));
+    steps(&mut app, 10);
+    assert!((pos(&app, coasting).x - 1.0).abs() < 1e-4);
+
+    {
+        let mut time = app.world_mut().resource_mut::<PhysicsTime>();
+        time.resume();
+        time.time_scale = 0.5;
+    }
+    steps(&mut app, 10);
+    assert!((pos(&app, coasting).x - 6.0).abs() < 1e-4);
+}
+
+#[test]
+fn input_held_while_paused_is_not_applied_on_the_next_step() {
+    let mut app = app();
+    let player = ball(&mut app, Vec2::ZERO, Vec2::ZERO, 5.0);
+    app.world_mut().resource_mut::<PhysicsTime>().pause();
+    hold_input(&mut app, &[(player, Vec2::X)], 10);
+
+    // Released before stepping, so nothing should push it
+    with_commands(&mut app, |commands| commands.queue(StepPhysics));
+    step(&mut app);
+    assert_eq!(vel(&app, player), Vec2::ZERO);
+    assert_eq!(pos(&app, player), Vec2::ZERO);
+}
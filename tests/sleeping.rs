@@ -0,0 +1,126 @@
+mod common;
+
+use common::*;
+use rustgamedev::prelude::*;
+
+fn sleeping(app: &App, entity: Entity) -> bool {
+    app.world().get::<Sleeping>(entity).is_some()
+}
+
+fn sleep_steps(app: &App) -> usize {
+    app.world().resource::<PhysicsConfig>().sleep_steps as usize
+}
+
+/// A ball that is allowed to fall asleep.
+fn sleepy_ball(app: &mut App, pos: Vec2, vel: Vec2, radius: f32) -> Entity {
+    let ball = ball(app, pos, vel, radius);
+    app.world_mut()
+        .entity_mut(ball)
+        .insert(SleepTimer::default());
+    ball
+}
+
+#[test]
+fn still_bodies_fall_asleep_and_wake_when_pushed() {
+    let mut app = app();
+    let idle = sleepy_ball(&mut app, Vec2::ZERO, Vec2::ZERO, 5.0);
+    let player = sleepy_ball(&mut app, Vec2::new(100.0, 0.0), Vec2::ZERO, 5.0);
+    let coasting = sleepy_ball(&mut app, Vec2::new(0.0, 100.0), Vec2::new(1000.0, 0.0), 5.0);
+    let restless = ball(&mut app, Vec2::new(-100.0, 0.0), Vec2::ZERO, 5.0);
+    frictionless(&mut app);
+    let n = sleep_steps(&app) + 1;
+    steps(&mut app, n);
+
+    assert!(sleeping(&app, idle) && sleeping(&app, player));
+    // Still too, but it never opted in
+    assert!(!sleeping(&app, coasting) && !sleeping(&app, restless));
+
+    with_commands(&mut app, |commands| {
+        commands.entity(idle).apply_impulse(Vec2::new(50.0, 0.0));
+    });
+    app.world_mut()
+        .get_mut::<AccumulatedInput>(player)
+        .unwrap()
+        .0 = Vec2::Y;
+    step(&mut app);
+
+    assert!(!sleeping(&app, idle) && vel(&app, idle).x > 0.0);
+    assert!(!sleeping(&app, player) && pos(&app, player).y > 0.0);
+}
+
+#[test]
+fn islands_sleep_together_and_wake_on_contact() {
+    let mut app = app();
+    frictionless(&mut app);
+    let zone = app
+        .world_mut()
+        .spawn((
+            Sensor,
+            Transform::default(),
+            Collider(ColliderShape::Rect {
+                half_extents: Vec2::splat(50.0),
+            }),
+        ))
+        .id();
+    // `a` and `c` are jointed into one island, the sensor ball only overlaps them
+    let a = sleepy_ball(&mut app, Vec2::ZERO, Vec2::ZERO, 10.0);
+    let c = sleepy_ball(&mut app, Vec2::new(30.0, 0.0), Vec2::ZERO, 10.0);
+    app.world_mut().spawn(DistanceJoint::new(a, c, 30.0));
+    let b = sleepy_ball(&mut app, Vec2::new(15.0, 0.0), Vec2::ZERO, 10.0);
+    app.world_mut().entity_mut(b).insert(Sensor);
+    // Overlaps the sensor ball but neither of the others
+    let walker = sleepy_ball(&mut app, Vec2::new(15.0, 18.0), Vec2::ZERO, 10.0);
+    let n = sleep_steps(&app) + 1;
+    steps(&mut app, n);
+    assert!(sleeping(&app, a) && sleeping(&app, b) && sleeping(&app, c));
+
+    // Pairs that are no longer tested keep touching instead of being reported as stopped
+    let mut cursor = event_cursor(&app);
+    steps(&mut app, 5);
+    assert!(contacts(&app).touching(a, b) && contacts(&app).touching(a, zone));
+    let stopped = read_events(&app, &mut cursor, |event| {
+        matches!(event, CollisionEvent::Stopped { .. })
+    });
+    assert!(!stopped.contains(&true));
+
+    // A sensor doesn't chain whatever overlaps it into one island
+    app.world_mut()
+        .get_mut::<AccumulatedInput>(walker)
+        .unwrap()
+        .0 = Vec2::Y;
+    step(&mut app);
+    assert!(!sleeping(&app, walker));
+    assert!(sleeping(&app, a) && sleeping(&app, b) && sleeping(&app, c));
+
+    let bullet = ball(
+        &mut app,
+        Vec2::new(-200.0, 0.0),
+        Vec2::new(2000.0, 0.0),
+        5.0,
+    );
+    steps(&mut app, 10);
+    assert!(!sleeping(&app, a) && !sleeping(&app, c));
+    assert!(pos(&app, a).x > 15.0);
+    assert!(vel(&app, bullet).x < 2000.0);
+}
+
+#[test]
+fn bodies_held_against_a_wall_stay_awake() {
+    let mut app = app();
+    wall(&mut app, Vec2::new(20.0, 0.0), Vec2::new(20.0, 200.0));
+    wall(&mut app, Vec2::new(-20.0, 100.0), Vec2::new(20.0, 200.0));
+    let walking = sleepy_ball(&mut app, Vec2::new(5.0, 0.0), Vec2::ZERO, 5.0);
+    let pushed = sleepy_ball(&mut app, Vec2::new(-5.0, 100.0), Vec2::ZERO, 5.0);
+    app.world_mut()
+        .entity_mut(pushed)
+        .insert(ExternalForce::persistent(Vec2::new(-500.0, 0.0)));
+
+    for _ in 0..sleep_steps(&app) * 2 {
+        app.world_mut()
+            .get_mut::<AccumulatedInput>(walking)
+            .unwrap()
+            .0 = Vec2::X;
+        step(&mut app);
+        assert!(!sleeping(&app, walking) && !sleeping(&app, pushed));
+    }
+}
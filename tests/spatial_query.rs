@@ -0,0 +1,132 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use common::*;
+use rustgamedev::prelude::*;
+
+#[test]
+fn casts_and_overlaps_find_every_kind_of_collider() {
+    let mut app = app();
+    let wall = wall(&mut app, Vec2::new(100.0, 0.0), Vec2::new(20.0, 200.0));
+    let mover = ball(&mut app, Vec2::new(50.0, 0.0), Vec2::ZERO, 10.0);
+    let triangle = PolygonHull::new(&[
+        Vec2::new(-10.0, -10.0),
+        Vec2::new(10.0, -10.0),
+        Vec2::new(0.0, 10.0),
+    ])
+    .unwrap();
+    let loose = app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(0.0, 100.0, 0.0),
+            Collider(ColliderShape::Polygon(triangle)),
+        ))
+        .id();
+    step(&mut app);
+
+    app.world_mut()
+        .run_system_once(move |query: SpatialQuery| {
+            let hit = query.cast_ray(Vec2::ZERO, Dir2::X, 500.0, &[]).unwrap();
+            assert_eq!(hit.entity, mover);
+            assert!((hit.distance - 40.0).abs() < 1e-3);
+
+            let hit = query
+                .cast_ray(Vec2::ZERO, Dir2::X, 500.0, &[mover])
+                .unwrap();
+            assert_eq!(hit.entity, wall);
+            assert!((hit.distance - 90.0).abs() < 1e-3);
+            assert_eq!(hit.normal, Vec2::NEG_X);
+
+            let hit = query
+                .cast_circle(Vec2::ZERO, 5.0, Dir2::X, 500.0, &[mover])
+                .unwrap();
+            assert!((hit.distance - 85.0).abs() < 1e-3);
+            assert!((hit.point.x - 90.0).abs() < 1e-3);
+
+            // Diagonal casts walk the wall grid cell by cell
+            let hit = query
+                .cast_ray(Vec2::ZERO, Dir2::NORTH_EAST, 500.0, &[])
+                .unwrap();
+            assert_eq!(hit.entity, wall);
+            assert!((hit.distance - 90.0 * 2f32.sqrt()).abs() < 1e-3);
+
+            let hit = query.cast_ray(Vec2::ZERO, Dir2::Y, 500.0, &[]).unwrap();
+            assert_eq!(hit.entity, loose);
+            assert!((hit.distance - 90.0).abs() < 1e-3);
+
+            // Starting inside a collider hits it straight away
+            let hit = query
+                .cast_ray(Vec2::new(100.0, 0.0), Dir2::X, 50.0, &[])
+                .unwrap();
+            assert_eq!(hit.distance, 0.0);
+            assert!(
+                query
+                    .cast_ray(Vec2::ZERO, Dir2::NEG_X, 500.0, &[])
+                    .is_none()
+            );
+
+            assert_eq!(query.overlap_point(Vec2::new(100.0, 50.0), &[]), vec![wall]);
+            assert_eq!(query.overlap_point(Vec2::new(0.0, 95.0), &[]), vec![loose]);
+            let mut overlapping = query.overlap_circle(Vec2::new(75.0, 0.0), 20.0, &[]);
+            overlapping.sort();
+            let mut expected = vec![wall, mover];
+            expected.sort();
+            assert_eq!(overlapping, expected);
+        })
+        .unwrap();
+}
+
+#[test]
+fn walls_are_found_before_the_first_step_and_while_paused() {
+    let mut app = app();
+    app.world_mut().resource_mut::<PhysicsTime>().pause();
+    let wall = wall(&mut app, Vec2::new(100.0, 0.0), Vec2::new(20.0, 200.0));
+    app.update();
+
+    app.world_mut()
+        .run_system_once(move |query: SpatialQuery| {
+            assert_eq!(query.overlap_point(Vec2::new(100.0, 0.0), &[]), vec![wall]);
+            let hit = query.cast_ray(Vec2::ZERO, Dir2::X, 500.0, &[]).unwrap();
+            assert_eq!(hit.entity, wall);
+            let hit = query
+                .cast_circle(Vec2::ZERO, 5.0, Dir2::X, 500.0, &[])
+                .unwrap();
+            assert!((hit.distance - 85.0).abs() < 1e-3);
+        })
+        .unwrap();
+}
+
+#[test]
+fn effector_regions_never_block_casts() {
+    let mut app = app();
+    let wall = wall(&mut app, Vec2::new(100.0, 0.0), Vec2::new(20.0, 200.0));
+    for sensor in [false, true] {
+        let zone = app
+            .world_mut()
+            .spawn((
+                AreaEffector::directional(Vec2::new(0.0, 100.0)),
+                Collider(ColliderShape::Rect {
+                    half_extents: Vec2::splat(20.0),
+                }),
+                Transform::from_xyz(50.0, 0.0, 0.0),
+            ))
+            .id();
+        if sensor {
+            app.world_mut().entity_mut(zone).insert(Sensor);
+        }
+    }
+    step(&mut app);
+
+    app.world_mut()
+        .run_system_once(move |query: SpatialQuery| {
+            let hit = query.cast_ray(Vec2::ZERO, Dir2::X, 500.0, &[]).unwrap();
+            assert_eq!(hit.entity, wall);
+            assert!(query.overlap_point(Vec2::new(50.0, 0.0), &[]).is_empty());
+            assert!(
+                query
+                    .overlap_circle(Vec2::new(50.0, 0.0), 5.0, &[])
+                    .is_empty()
+            );
+        })
+        .unwrap();
+}